@@ -0,0 +1,61 @@
+//! Compares `fill_indexed` against seeding with each index in turn.
+//!
+//! ```sh
+//! cargo bench --bench fill_indexed
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use pure_rng::PureRng;
+
+const LENGTHS: [usize; 3] = [16, 1024, 65536];
+
+fn bench_u64(c: &mut Criterion) {
+    let rng = PureRng::new("bench");
+    let mut group = c.benchmark_group("u64");
+
+    for len in LENGTHS {
+        let mut values = vec![0u64; len];
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("seed", len), &len, |b, _| {
+            b.iter(|| {
+                for (i, value) in values.iter_mut().enumerate() {
+                    *value = black_box(&rng).seed(i as u64).gen();
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("fill_indexed", len), &len, |b, _| {
+            b.iter(|| black_box(&rng).fill_indexed(0, &mut values))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_f64(c: &mut Criterion) {
+    let rng = PureRng::new("bench");
+    let mut group = c.benchmark_group("f64");
+
+    for len in LENGTHS {
+        let mut values = vec![0f64; len];
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("seed", len), &len, |b, _| {
+            b.iter(|| {
+                for (i, value) in values.iter_mut().enumerate() {
+                    *value = black_box(&rng).seed(i as u64).gen();
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("fill_indexed_f64", len), &len, |b, _| {
+            b.iter(|| black_box(&rng).fill_indexed_f64(0, &mut values))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_u64, bench_f64);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+//! Compares drawing from a `PureRng` directly against converting it with
+//! `into_stream` first.
+//!
+//! ```sh
+//! cargo bench --bench stream
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use pure_rng::{PureRng, PureStream};
+use rand::{seq::SliceRandom, RngCore};
+
+const LENGTHS: [usize; 2] = [1024, 100_000];
+
+fn bench_shuffle(c: &mut Criterion) {
+    let rng = PureRng::new("bench");
+    let mut group = c.benchmark_group("shuffle");
+
+    for len in LENGTHS {
+        let mut deck: Vec<u32> = (0..len as u32).collect();
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("pure", len), &len, |b, _| {
+            b.iter(|| deck.shuffle(&mut black_box(rng)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("into_stream", len), &len, |b, _| {
+            b.iter(|| deck.shuffle(&mut black_box(rng).into_stream::<PureStream>()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_next_u64(c: &mut Criterion) {
+    let rng = PureRng::new("bench");
+    let mut group = c.benchmark_group("next_u64");
+
+    for len in LENGTHS {
+        group.throughput(Throughput::Elements(len as u64));
+
+        group.bench_with_input(BenchmarkId::new("pure", len), &len, |b, &len| {
+            b.iter(|| {
+                let mut rng = black_box(rng);
+                (0..len).fold(0, |acc, _| acc ^ rng.next_u64())
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("into_stream", len), &len, |b, &len| {
+            b.iter(|| {
+                let mut rng = black_box(rng).into_stream::<PureStream>();
+                (0..len).fold(0, |acc, _| acc ^ rng.next_u64())
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_shuffle, bench_next_u64);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+use std::process::ExitCode;
+
+use pure_rng::record::Log;
+
+/// Compares two logs written by `pure_rng::record`, eg. from the same world
+/// seed on two different builds, and reports the first divergent seed path.
+///
+/// ```sh
+/// cargo run --example compare_records --features record -- old.log new.log
+/// ```
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let [left, right] = &args[..] else {
+        eprintln!("usage: compare_records <left.log> <right.log>");
+        return ExitCode::from(2);
+    };
+
+    let load = |path: &str| {
+        Log::load(path).unwrap_or_else(|err| {
+            eprintln!("failed to read {path}: {err}");
+            std::process::exit(2);
+        })
+    };
+
+    match load(left).first_divergence(&load(right)) {
+        Some(divergence) => {
+            println!("first divergence at {divergence}");
+            ExitCode::FAILURE
+        }
+        None => {
+            println!("no divergence");
+            ExitCode::SUCCESS
+        }
+    }
+}
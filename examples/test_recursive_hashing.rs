@@ -3,6 +3,7 @@ use std::{
     io::{self, Write},
 };
 
+use pure_rng::StableHasher;
 use rapidhash::RapidHasher;
 
 /// Sends an endless stream of random bytes to stdout, using the recursive
@@ -15,7 +16,7 @@ use rapidhash::RapidHasher;
 /// cargo run --release --example test_recursive_hashing -- "my seed value" | RNG_test stdin64 -multithreaded
 /// ```
 fn main() {
-    let mut hasher = RapidHasher::default();
+    let mut hasher = StableHasher::<RapidHasher>::default();
     if let Some(arg) = std::env::args().nth(1) {
         arg.hash(&mut hasher);
     }
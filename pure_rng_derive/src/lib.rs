@@ -0,0 +1,215 @@
+//! Derive macros for [`pure_rng`](https://crates.io/crates/pure_rng). Enable
+//! the `derive` feature of `pure_rng` rather than depending on this crate
+//! directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Expr, Fields, LitStr,
+    Result, Type, WherePredicate,
+};
+
+/// Derives `pure_rng::PureGenerate` for a struct.
+///
+/// Each field is generated from the generator seeded with the field's name,
+/// or its index for tuple structs. Fields can be customised with the `pure`
+/// attribute:
+///
+/// * `#[pure(range = 1..10)]` samples uniformly from the given range.
+/// * `#[pure(dist = Normal::new(20., 6.).unwrap())]` samples from the given
+///   `rand` distribution.
+/// * `#[pure(generate)]` uses the field type's own `PureGenerate`
+///   implementation.
+/// * `#[pure(seed = "name")]` seeds the field with the given name instead of
+///   its own, eg. to keep its values after renaming it.
+///
+/// Fields without `range`, `dist` or `generate` are sampled from the
+/// `Standard` distribution.
+///
+/// For generic structs, fields whose types use the struct's type parameters
+/// get the bounds they need added to the `where` clause, eg. `T: PureGenerate`
+/// for a `#[pure(generate)]` field of type `T`. Bounds for `range` and `dist`
+/// fields must be written by hand.
+#[proc_macro_derive(PureGenerate, attributes(pure))]
+pub fn derive_pure_generate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Source {
+    Standard,
+    Range(Expr),
+    Distribution(Expr),
+    Generate,
+}
+
+struct FieldOptions {
+    seed: Option<LitStr>,
+    source: Source,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "PureGenerate can only be derived for structs",
+        ));
+    };
+
+    let name = &input.ident;
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let mut bounds = Vec::new();
+    let mut value = |field: &syn::Field, label: &str| {
+        let options = field_options(field)?;
+
+        if uses_params(&field.ty, &params) {
+            bounds.extend(field_bound(&field.ty, &options.source));
+        }
+
+        Ok(field_value(field, label, options))
+    };
+
+    let body = match &data.fields {
+        Fields::Named(fields) => {
+            let fields = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.as_ref().unwrap();
+                    let label = ident.to_string();
+                    let label = label.strip_prefix("r#").unwrap_or(&label);
+                    let value = value(field, label)?;
+
+                    Ok(quote! { #ident: #value })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            quote! { Self { #(#fields),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let fields = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, field)| value(field, &i.to_string()))
+                .collect::<Result<Vec<_>>>()?;
+
+            quote! { Self(#(#fields),*) }
+        }
+        Fields::Unit => quote! { Self },
+    };
+
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::pure_rng::PureGenerate for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn generate<__H>(rng: ::pure_rng::PureRandomGenerator<__H>) -> Self
+            where
+                __H: ::core::hash::Hasher + ::core::default::Default + ::core::clone::Clone,
+            {
+                #body
+            }
+        }
+    })
+}
+
+fn field_value(field: &syn::Field, label: &str, options: FieldOptions) -> TokenStream2 {
+    let label = options
+        .seed
+        .unwrap_or_else(|| LitStr::new(label, field.span()));
+    let ty = &field.ty;
+    let rng = quote! { rng.seed(#label) };
+
+    match options.source {
+        Source::Standard => quote! {
+            ::pure_rng::__private::sample_standard::<#ty, __H>(#rng)
+        },
+        Source::Range(range) => quote! {
+            ::pure_rng::__private::sample_range::<#ty, _, __H>(#rng, #range)
+        },
+        Source::Distribution(distr) => quote! {
+            ::pure_rng::__private::sample_distribution::<#ty, _, __H>(#rng, #distr)
+        },
+        Source::Generate => quote! {
+            <#ty as ::pure_rng::PureGenerate>::generate(#rng)
+        },
+    }
+}
+
+/// Returns the bound a field of the given type needs, if any. The bounds for
+/// `range` and `dist` fields depend on the type of the given expression, so
+/// are left to the caller.
+fn field_bound(ty: &Type, source: &Source) -> Option<WherePredicate> {
+    match source {
+        Source::Standard => Some(parse_quote! {
+            ::pure_rng::__private::Standard: ::pure_rng::__private::Distribution<#ty>
+        }),
+        Source::Range(_) | Source::Distribution(_) => None,
+        Source::Generate => Some(parse_quote! {
+            #ty: ::pure_rng::PureGenerate
+        }),
+    }
+}
+
+fn uses_params(ty: &Type, params: &[Ident]) -> bool {
+    fn walk(tokens: TokenStream2, params: &[Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => params.contains(&ident),
+            TokenTree::Group(group) => walk(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    walk(ty.to_token_stream(), params)
+}
+
+fn field_options(field: &syn::Field) -> Result<FieldOptions> {
+    let mut options = FieldOptions {
+        seed: None,
+        source: Source::Standard,
+    };
+    let mut has_source = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pure"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let source = if meta.path.is_ident("seed") {
+                options.seed = Some(meta.value()?.parse()?);
+                return Ok(());
+            } else if meta.path.is_ident("range") {
+                Source::Range(meta.value()?.parse()?)
+            } else if meta.path.is_ident("dist") {
+                Source::Distribution(meta.value()?.parse()?)
+            } else if meta.path.is_ident("generate") {
+                Source::Generate
+            } else {
+                return Err(meta.error("expected `range`, `dist`, `generate` or `seed`"));
+            };
+
+            if has_source {
+                return Err(meta.error("only one of `range`, `dist` or `generate` may be given"));
+            }
+
+            has_source = true;
+            options.source = source;
+
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
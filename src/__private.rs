@@ -0,0 +1,52 @@
+//! Support code for `pure_rng_derive`. Not public API.
+
+use core::hash::Hasher;
+
+use crate::PureRandomGenerator;
+
+#[cfg(feature = "rand_08")]
+pub use rand::distributions::{
+    uniform::{SampleRange, SampleUniform},
+    Distribution, Standard,
+};
+#[cfg(all(feature = "rand_09", not(feature = "rand_08")))]
+pub use rand09::distr::{
+    uniform::{SampleRange, SampleUniform},
+    Distribution, StandardUniform as Standard,
+};
+
+pub fn sample_standard<T, H>(rng: PureRandomGenerator<H>) -> T
+where
+    H: Hasher + Default + Clone,
+    Standard: Distribution<T>,
+{
+    sample_distribution(rng, Standard)
+}
+
+#[cfg(feature = "rand_08")]
+pub fn sample_range<T, R, H>(rng: PureRandomGenerator<H>, range: R) -> T
+where
+    H: Hasher + Default + Clone,
+    T: SampleUniform,
+    R: SampleRange<T>,
+{
+    rng.gen_range(range)
+}
+
+#[cfg(all(feature = "rand_09", not(feature = "rand_08")))]
+pub fn sample_range<T, R, H>(rng: PureRandomGenerator<H>, range: R) -> T
+where
+    H: Hasher + Default + Clone,
+    T: SampleUniform,
+    R: SampleRange<T>,
+{
+    rng.random_range(range)
+}
+
+pub fn sample_distribution<T, D, H>(rng: PureRandomGenerator<H>, distr: D) -> T
+where
+    H: Hasher + Default + Clone,
+    D: Distribution<T>,
+{
+    rng.sample(distr)
+}
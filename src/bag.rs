@@ -0,0 +1,495 @@
+//! Shuffle bags and decks, where every item comes up once per cycle.
+//!
+//! `SlicePureRandom::shuffle` gives a single shuffle. A [`ShuffleBag`] instead
+//! deals its items forever, in a fresh order each time round, like the 7-bag
+//! in Tetris. A [`Deck`] deals one shuffle at a time, until it's explicitly
+//! reshuffled.
+//!
+//! Draws are pure: draw `n` of a bag is item `order[n % len]`, where `order` is
+//! a shuffle of the items seeded by `rng.seed(n / len)`, with the cycle as a
+//! `u64`. Here `rng` is the generator returned alongside the bag's
+//! [`PureRngState`] by [`to_state`](PureRandomGenerator::to_state), so that it
+//! can be restored from the snapshot. Any draw can be computed directly with
+//! [`draw_at`](ShuffleBag::draw_at), without replaying the ones before it.
+//!
+//! A bag is saved with [`state`](ShuffleBag::state), which gives a [`BagState`]
+//! holding just the snapshot of its generator and its cursor. This doesn't
+//! depend on the hasher supporting serde, and serializes with the `serde`
+//! feature. The items are passed back in when restoring it with
+//! [`from_state`](ShuffleBag::from_state).
+//!
+//! The draws are independent of the `rand` version, and are covered by the
+//! value-stability policy described on [`STREAM_VERSION`](crate::STREAM_VERSION).
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{bag::ShuffleBag, PureRng};
+//!
+//! let items = vec!['I', 'J', 'L', 'O', 'S', 'T', 'Z'];
+//! let mut pieces = ShuffleBag::new(items.clone(), PureRng::new(7));
+//!
+//! let mut first: Vec<char> = (0..7).map(|_| *pieces.draw()).collect();
+//! first.sort();
+//! assert_eq!(first, ['I', 'J', 'L', 'O', 'S', 'T', 'Z']);
+//!
+//! // Jump straight to the 10,000th piece.
+//! let piece = *pieces.draw_at(9_999);
+//! pieces.seek(9_999);
+//! assert_eq!(*pieces.draw(), piece);
+//!
+//! // Save the bag and pick up where it left off.
+//! let state = pieces.state();
+//! let mut restored = ShuffleBag::from_state(items, state).unwrap();
+//! assert!(restored == pieces);
+//! assert_eq!(restored.draw(), pieces.draw());
+//! # }
+//! ```
+
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use crate::{PureRandomGenerator, PureRngState, StateError};
+
+/// A bag dealing its items in a new shuffled order every cycle.
+///
+/// See the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ShuffleBag<T, H>
+where
+    H: Hasher + Default + Clone,
+{
+    items: Vec<T>,
+    state: PureRngState,
+    rng: PureRandomGenerator<H>,
+    cursor: u64,
+    order: Order,
+}
+
+/// The saved state of a [`ShuffleBag`], without its items.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BagState {
+    /// The snapshot of the bag's generator.
+    pub rng: PureRngState,
+    /// The index of the next draw.
+    pub cursor: u64,
+}
+
+/// The shuffled order of one cycle, cached between draws.
+#[derive(Debug, Clone, Default)]
+struct Order {
+    cycle: Option<u64>,
+    indices: Vec<usize>,
+}
+
+impl<T, H> ShuffleBag<T, H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a bag of the given items, with its cursor at the start.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is empty.
+    pub fn new(items: Vec<T>, rng: PureRandomGenerator<H>) -> Self {
+        let (state, rng) = rng.to_state();
+
+        Self::with_state(items, state, rng, 0)
+    }
+
+    /// Restores a bag saved with [`state`](Self::state), given the same items
+    /// it was created with.
+    ///
+    /// Fails if the generator can't be restored, as described on
+    /// [`PureRandomGenerator::from_state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `items` is empty.
+    pub fn from_state(items: Vec<T>, state: BagState) -> Result<Self, StateError> {
+        let rng = PureRandomGenerator::from_state(state.rng)?;
+
+        Ok(Self::with_state(items, state.rng, rng, state.cursor))
+    }
+
+    fn with_state(
+        items: Vec<T>,
+        state: PureRngState,
+        rng: PureRandomGenerator<H>,
+        cursor: u64,
+    ) -> Self {
+        assert!(!items.is_empty(), "a shuffle bag needs at least one item");
+
+        Self {
+            items,
+            state,
+            rng,
+            cursor,
+            order: Order::default(),
+        }
+    }
+
+    /// Returns the bag's generator snapshot and cursor, from which it can be
+    /// restored with [`from_state`](Self::from_state).
+    pub fn state(&self) -> BagState {
+        BagState {
+            rng: self.state,
+            cursor: self.cursor,
+        }
+    }
+
+    /// Draws the item at the cursor, and advances it.
+    pub fn draw(&mut self) -> &T {
+        let n = self.cursor;
+        self.cursor = self.cursor.wrapping_add(1);
+
+        let (cycle, position) = self.split(n);
+        self.item(cycle, position)
+    }
+
+    /// Returns the item at the cursor, without advancing it.
+    pub fn peek(&self) -> &T {
+        self.draw_at(self.cursor)
+    }
+
+    /// Returns the item of draw `n`, regardless of the cursor.
+    ///
+    /// This shuffles the whole cycle containing the draw, unless it's already
+    /// been shuffled for [`draw`](Self::draw), so it takes time proportional
+    /// to the number of items.
+    pub fn draw_at(&self, n: u64) -> &T {
+        let (cycle, position) = self.split(n);
+        self.item_at(cycle, position)
+    }
+
+    /// The number of items drawn so far, which is also the index of the next
+    /// draw.
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    /// Moves the cursor, so the next draw is draw `n`.
+    pub fn seek(&mut self, n: u64) {
+        self.cursor = n;
+    }
+
+    /// The number of draws left before the next cycle starts.
+    pub fn remaining_in_cycle(&self) -> usize {
+        self.items.len() - self.split(self.cursor).1
+    }
+
+    /// The items in the bag, in their original order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the item at the given position of a cycle, caching the cycle's
+    /// order.
+    fn item(&mut self, cycle: u64, position: usize) -> &T {
+        if self.order.cycle != Some(cycle) {
+            self.order = Order {
+                cycle: Some(cycle),
+                indices: self.shuffle(cycle),
+            };
+        }
+
+        &self.items[self.order.indices[position]]
+    }
+
+    /// Returns the item at the given position of a cycle, using the cached
+    /// order if it's for that cycle.
+    fn item_at(&self, cycle: u64, position: usize) -> &T {
+        let index = if self.order.cycle == Some(cycle) {
+            self.order.indices[position]
+        } else {
+            self.shuffle(cycle)[position]
+        };
+
+        &self.items[index]
+    }
+
+    /// Splits a draw into its cycle and its position in the cycle.
+    fn split(&self, n: u64) -> (u64, usize) {
+        let len = self.items.len() as u64;
+
+        (n / len, (n % len) as usize)
+    }
+
+    /// Shuffles the indices of the items for the given cycle, with a
+    /// Fisher-Yates shuffle.
+    fn shuffle(&self, cycle: u64) -> Vec<usize> {
+        let mut rng = self.rng.seed(cycle);
+        let mut indices: Vec<usize> = (0..self.items.len()).collect();
+
+        for i in (1..indices.len()).rev() {
+            let j = rng.draw_below(i as u64 + 1) as usize;
+            indices.swap(i, j);
+        }
+
+        indices
+    }
+}
+
+impl<T, H> PartialEq for ShuffleBag<T, H>
+where
+    T: PartialEq,
+    H: Hasher + Default + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items && self.state == other.state && self.cursor == other.cursor
+    }
+}
+
+impl<T, H> Eq for ShuffleBag<T, H>
+where
+    T: Eq,
+    H: Hasher + Default + Clone,
+{
+}
+
+/// A deck dealing one shuffle of its items at a time.
+///
+/// Once every card has been drawn, [`draw`](Self::draw) returns `None` until
+/// the deck is [reshuffled](Self::reshuffle). Deal `d` of a deck is cycle `d`
+/// of the equivalent [`ShuffleBag`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck<T, H>
+where
+    H: Hasher + Default + Clone,
+{
+    bag: ShuffleBag<T, H>,
+    deal: u64,
+    drawn: usize,
+}
+
+/// The saved state of a [`Deck`], without its cards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeckState {
+    /// The snapshot of the deck's generator.
+    pub rng: PureRngState,
+    /// The number of times the deck has been reshuffled.
+    pub deal: u64,
+    /// The number of cards drawn in this deal.
+    pub drawn: usize,
+}
+
+impl<T, H> Deck<T, H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a deck of the given cards, ready for the first deal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cards` is empty.
+    pub fn new(cards: Vec<T>, rng: PureRandomGenerator<H>) -> Self {
+        Self {
+            bag: ShuffleBag::new(cards, rng),
+            deal: 0,
+            drawn: 0,
+        }
+    }
+
+    /// Restores a deck saved with [`state`](Self::state), given the same
+    /// cards it was created with. If more cards were drawn than there are
+    /// now, the deal is restored as finished.
+    ///
+    /// Fails if the generator can't be restored, as described on
+    /// [`PureRandomGenerator::from_state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cards` is empty.
+    pub fn from_state(cards: Vec<T>, state: DeckState) -> Result<Self, StateError> {
+        let bag_state = BagState {
+            rng: state.rng,
+            cursor: 0,
+        };
+        let bag = ShuffleBag::from_state(cards, bag_state)?;
+        let drawn = state.drawn.min(bag.items.len());
+
+        Ok(Self {
+            bag,
+            deal: state.deal,
+            drawn,
+        })
+    }
+
+    /// Returns the deck's generator snapshot, deal and number of cards drawn,
+    /// from which it can be restored with [`from_state`](Self::from_state).
+    pub fn state(&self) -> DeckState {
+        DeckState {
+            rng: self.bag.state,
+            deal: self.deal,
+            drawn: self.drawn,
+        }
+    }
+
+    /// Draws the top card, if any are left in this deal.
+    pub fn draw(&mut self) -> Option<&T> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        let position = self.drawn;
+        self.drawn += 1;
+
+        Some(self.bag.item(self.deal, position))
+    }
+
+    /// Returns the top card without drawing it, if any are left in this deal.
+    pub fn peek(&self) -> Option<&T> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        Some(self.bag.item_at(self.deal, self.drawn))
+    }
+
+    /// The number of cards left in this deal.
+    pub fn remaining(&self) -> usize {
+        self.bag.items.len() - self.drawn
+    }
+
+    /// Shuffles every card back into the deck, starting the next deal.
+    pub fn reshuffle(&mut self) {
+        self.deal = self.deal.wrapping_add(1);
+        self.drawn = 0;
+    }
+
+    /// The number of times the deck has been reshuffled.
+    pub fn deal(&self) -> u64 {
+        self.deal
+    }
+
+    /// The cards in the deck, in their original order.
+    pub fn cards(&self) -> &[T] {
+        self.bag.items()
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use rapidhash::RapidHasher;
+
+    use super::*;
+    use crate::{PureRng, StableHasher};
+
+    fn bag() -> ShuffleBag<u32, StableHasher<RapidHasher>> {
+        ShuffleBag::new((0..10).collect(), PureRng::new("bag"))
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut bag = bag();
+        let mut cycles = Vec::new();
+
+        for _ in 0..5 {
+            assert_eq!(bag.remaining_in_cycle(), 10);
+            let mut cycle: Vec<u32> = (0..10).map(|_| *bag.draw()).collect();
+            cycles.push(cycle.clone());
+
+            cycle.sort();
+            assert_eq!(cycle, (0..10).collect::<Vec<_>>());
+        }
+
+        cycles.dedup();
+        assert_eq!(cycles.len(), 5);
+    }
+
+    #[test]
+    fn test_draw_at() {
+        let mut bag = bag();
+        let expected: Vec<u32> = (0..35).map(|n| *bag.draw_at(n)).collect();
+        let drawn: Vec<u32> = (0..35).map(|_| *bag.draw()).collect();
+        assert_eq!(drawn, expected);
+
+        let far = *bag.draw_at(10_000_000);
+        bag.seek(10_000_000);
+        assert_eq!(*bag.peek(), far);
+        assert_eq!(*bag.draw(), far);
+        assert_eq!(bag.cursor(), 10_000_001);
+        assert_eq!(bag.remaining_in_cycle(), 9);
+    }
+
+    #[test]
+    fn test_deck() {
+        let mut deck = Deck::new((0..10).collect(), PureRng::new("bag"));
+        let mut bag = bag();
+
+        for deal in 0..3 {
+            assert_eq!(deck.deal(), deal);
+            for remaining in (1..=10).rev() {
+                assert_eq!(deck.remaining(), remaining);
+                assert_eq!(deck.draw(), Some(bag.draw()));
+            }
+
+            assert_eq!(deck.peek(), None);
+            assert_eq!(deck.draw(), None);
+            deck.reshuffle();
+        }
+
+        // Reshuffling part way through skips the rest of the deal.
+        deck.draw();
+        deck.reshuffle();
+        assert_eq!(deck.remaining(), 10);
+        assert_eq!(deck.draw(), Some(bag.draw_at(40)));
+    }
+
+    #[test]
+    fn test_state() {
+        let mut bag = bag();
+        bag.seek(12);
+
+        let mut restored = ShuffleBag::from_state(bag.items().to_vec(), bag.state()).unwrap();
+        assert!(restored == bag);
+        assert_eq!(restored.draw(), bag.draw());
+
+        let mut deck = Deck::new((0..10).collect(), PureRng::new("bag"));
+        deck.reshuffle();
+        deck.draw();
+
+        let mut restored = Deck::from_state(deck.cards().to_vec(), deck.state()).unwrap();
+        assert!(restored == deck);
+        assert_eq!(restored.draw(), deck.draw());
+
+        // A deal can't have more cards drawn than the deck has.
+        let restore = |state| -> Deck<u32, StableHasher<RapidHasher>> {
+            Deck::from_state((0..10).collect(), state).unwrap()
+        };
+        let mut state = deck.state();
+        state.drawn = usize::MAX;
+        let mut restored = restore(state);
+        assert_eq!(restored.remaining(), 0);
+        assert_eq!(restored.draw(), None);
+
+        // Nor does a far off deal overflow.
+        state.deal = u64::MAX;
+        state.drawn = 3;
+        let mut restored = restore(state);
+        assert_eq!(restored.remaining(), 7);
+        assert_eq!(restored.draw(), Some(bag.item_at(u64::MAX, 3)));
+        restored.reshuffle();
+        assert_eq!(restored.deal(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut bag = bag();
+        bag.draw();
+        bag.draw();
+
+        let json = serde_json::to_string(&bag.state()).unwrap();
+        let mut restored =
+            ShuffleBag::from_state(bag.items().to_vec(), serde_json::from_str(&json).unwrap())
+                .unwrap();
+
+        assert!(restored == bag);
+        assert_eq!(restored.draw(), bag.draw());
+    }
+}
@@ -0,0 +1,510 @@
+//! Dice notation, enabled by the `dice` feature.
+//!
+//! Parses expressions like `3d6+2`, `4d6kh3`, `d%` and `2d10!` into an
+//! [`Expr`], which can be rolled with a [`PureRandomGenerator`] or analysed
+//! for its exact probability distribution.
+//!
+//! The supported notation is:
+//!
+//! | Notation        | Meaning                                                    |
+//! |-----------------|------------------------------------------------------------|
+//! | `3d6`, `d20`    | Roll dice and add them up. The count defaults to 1         |
+//! | `d%`            | Percentile dice, the same as `d100`                        |
+//! | `4dF`           | Fudge dice, each -1, 0 or 1                                |
+//! | `4d6kh3`, `k3`  | Keep the highest 3 dice                                    |
+//! | `2d20kl1`       | Keep the lowest die                                        |
+//! | `4d6dl1`, `dh1` | Drop the lowest or highest dice                            |
+//! | `3d6!`          | Exploding dice: on the highest face, roll again and add it |
+//! | `+ - *`, `( )`  | Arithmetic on integers and dice, with the usual precedence |
+//!
+//! Exploding dice compound, so a die's value is the sum of its chain of rolls,
+//! and keeping or dropping dice compares these sums. A die explodes at most
+//! [`MAX_EXPLOSIONS`] times.
+//!
+//! Each die is rolled from its own generator: die `i` of the `t`th dice term
+//! in the expression is rolled from `rng.seed(t).seed(i)`, with both indices as
+//! `u32`. Adding a modifier or a constant therefore doesn't change the dice
+//! that are rolled, and adding dice to a term only adds new ones. The rolls
+//! are independent of the `rand` version, and are covered by the
+//! value-stability policy described on [`STREAM_VERSION`](crate::STREAM_VERSION).
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{dice::Expr, PureRng};
+//!
+//! let attack: Expr = "1d20+5".parse().unwrap();
+//! let stats: Expr = "4d6kh3".parse().unwrap();
+//!
+//! let rng = PureRng::new("character");
+//! let strength = stats.roll(rng.seed("strength"));
+//! assert!((3..=18).contains(&strength.total));
+//! assert_eq!(strength.dice.iter().filter(|die| die.kept).count(), 3);
+//!
+//! let odds = attack.distribution().unwrap();
+//! assert!((odds.at_least(15) - 0.55).abs() < 1e-9);
+//! # }
+//! ```
+
+mod parse;
+mod probability;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt::{self, Display, Formatter},
+    hash::Hasher,
+};
+
+pub use parse::DiceError;
+pub use probability::Probabilities;
+
+use crate::{distributions::PureDistribution, PureRandomGenerator};
+
+/// The most times a single die can explode.
+pub const MAX_EXPLOSIONS: u32 = 100;
+
+/// The most work [`Expr::distribution`] does before giving up with
+/// [`DiceError::TooComplex`].
+///
+/// Each step adds one pair of outcomes into a distribution, so combining two
+/// distributions takes the product of their numbers of outcomes. This allows
+/// a few hundred d6 or a few dozen d100, but not `1000d1000`.
+pub const MAX_COMPLEXITY: u64 = 10_000_000;
+
+/// A parsed dice expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// An integer.
+    Constant(i64),
+    /// A term rolling one or more dice.
+    Dice(Dice),
+    /// The negation of an expression.
+    Neg(Box<Expr>),
+    /// The sum of two expressions.
+    Add(Box<Expr>, Box<Expr>),
+    /// The difference of two expressions.
+    Sub(Box<Expr>, Box<Expr>),
+    /// The product of two expressions.
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+/// A term rolling one or more identical dice, eg. `4d6kh3`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Dice {
+    /// The number of dice.
+    pub count: u32,
+    /// The faces of each die.
+    pub sides: Sides,
+    /// Which of the dice count towards the total.
+    pub keep: Keep,
+    /// Whether a die rolls again and adds the result when it shows its
+    /// highest face.
+    pub explode: bool,
+}
+
+/// The faces of a die.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sides {
+    /// A die numbered from 1 up to the given number.
+    Number(u32),
+    /// A Fudge die, showing -1, 0 or 1.
+    Fudge,
+}
+
+/// Which dice of a term count towards its total.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Keep {
+    /// Every die.
+    All,
+    /// The given number of highest dice.
+    Highest(u32),
+    /// The given number of lowest dice.
+    Lowest(u32),
+}
+
+/// The result of rolling an [`Expr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roll {
+    /// The value of the expression.
+    pub total: i64,
+    /// Every die rolled, in the order of the terms and then the dice within
+    /// them.
+    pub dice: Vec<DieRoll>,
+}
+
+/// A single die from a [`Roll`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DieRoll {
+    /// The index of the dice term this die belongs to, counting only dice
+    /// terms from the left of the expression.
+    pub term: u32,
+    /// The index of the die within its term.
+    pub index: u32,
+    /// The value of the die, including any explosions.
+    pub value: i64,
+    /// The number of times the die exploded.
+    pub explosions: u32,
+    /// Whether the die counts towards the total.
+    pub kept: bool,
+}
+
+impl Expr {
+    /// Parses a dice expression. Equivalent to `str::parse`.
+    pub fn parse(expr: &str) -> Result<Self, DiceError> {
+        parse::parse(expr)
+    }
+
+    /// Rolls the expression, returning the total and every die rolled.
+    pub fn roll<H>(&self, rng: PureRandomGenerator<H>) -> Roll
+    where
+        H: Hasher + Default + Clone,
+    {
+        let mut dice = Vec::new();
+        let total = self.evaluate(&rng, &mut 0, &mut dice);
+
+        Roll { total, dice }
+    }
+
+    /// Calculates the exact probability of every possible total.
+    ///
+    /// The calculation convolves the distributions of the individual dice
+    /// rather than sampling, so it's exact up to floating point rounding.
+    /// Exploding chains are followed until their probability drops below
+    /// 10^-20, and the last roll is taken as final.
+    ///
+    /// # Errors
+    ///
+    /// The work needed grows with the range of totals, so rather than run for
+    /// an unbounded time on expressions like `1000d1000`, this returns
+    /// [`DiceError::TooComplex`] once it would take more than
+    /// [`MAX_COMPLEXITY`] steps.
+    pub fn distribution(&self) -> Result<Probabilities, DiceError> {
+        self.distribution_within(&mut probability::Budget::new())
+    }
+
+    fn distribution_within(
+        &self,
+        budget: &mut probability::Budget,
+    ) -> Result<Probabilities, DiceError> {
+        match self {
+            Self::Constant(value) => Ok(Probabilities::constant(*value)),
+            Self::Dice(dice) => probability::dice(dice, budget),
+            Self::Neg(expr) => Ok(expr
+                .distribution_within(budget)?
+                .map(|value| value.saturating_neg())),
+            Self::Add(a, b) => a.distribution_within(budget)?.combine(
+                &b.distribution_within(budget)?,
+                budget,
+                i64::saturating_add,
+            ),
+            Self::Sub(a, b) => a.distribution_within(budget)?.combine(
+                &b.distribution_within(budget)?,
+                budget,
+                i64::saturating_sub,
+            ),
+            Self::Mul(a, b) => a.distribution_within(budget)?.combine(
+                &b.distribution_within(budget)?,
+                budget,
+                i64::saturating_mul,
+            ),
+        }
+    }
+
+    fn evaluate<H>(
+        &self,
+        rng: &PureRandomGenerator<H>,
+        terms: &mut u32,
+        dice: &mut Vec<DieRoll>,
+    ) -> i64
+    where
+        H: Hasher + Default + Clone,
+    {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Dice(term) => {
+                let index = *terms;
+                *terms += 1;
+                term.roll(rng.seed(index), index, dice)
+            }
+            Self::Neg(expr) => expr.evaluate(rng, terms, dice).saturating_neg(),
+            Self::Add(a, b) => {
+                let a = a.evaluate(rng, terms, dice);
+                a.saturating_add(b.evaluate(rng, terms, dice))
+            }
+            Self::Sub(a, b) => {
+                let a = a.evaluate(rng, terms, dice);
+                a.saturating_sub(b.evaluate(rng, terms, dice))
+            }
+            Self::Mul(a, b) => {
+                let a = a.evaluate(rng, terms, dice);
+                a.saturating_mul(b.evaluate(rng, terms, dice))
+            }
+        }
+    }
+
+    /// The binding strength of the expression's operator, for deciding where
+    /// to put brackets when displaying it.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add(..) | Self::Sub(..) => 1,
+            Self::Mul(..) => 2,
+            Self::Neg(..) => 3,
+            Self::Constant(..) | Self::Dice(..) => 4,
+        }
+    }
+}
+
+impl Dice {
+    /// Rolls the dice of this term, adding them to `dice` and returning their
+    /// total.
+    fn roll<H>(&self, rng: PureRandomGenerator<H>, term: u32, dice: &mut Vec<DieRoll>) -> i64
+    where
+        H: Hasher + Default + Clone,
+    {
+        let first = dice.len();
+
+        for index in 0..self.count {
+            let mut die = rng.seed(index);
+            let mut face = self.sides.roll(&mut die);
+            let mut value = face;
+            let mut explosions = 0;
+
+            while self.explode && face == self.sides.highest() && explosions < MAX_EXPLOSIONS {
+                face = self.sides.roll(&mut die);
+                value += face;
+                explosions += 1;
+            }
+
+            dice.push(DieRoll {
+                term,
+                index,
+                value,
+                explosions,
+                kept: true,
+            });
+        }
+
+        let rolled = &mut dice[first..];
+        if let Keep::Highest(keep) | Keep::Lowest(keep) = self.keep {
+            // Sort the dice by value, breaking ties by index, and drop those
+            // past the number to keep.
+            let mut order: Vec<usize> = (0..rolled.len()).collect();
+            order.sort_by_key(|&i| rolled[i].value);
+            if let Keep::Highest(_) = self.keep {
+                order.sort_by_key(|&i| core::cmp::Reverse(rolled[i].value));
+            }
+
+            for &i in order.iter().skip(keep as usize) {
+                rolled[i].kept = false;
+            }
+        }
+
+        rolled
+            .iter()
+            .filter(|die| die.kept)
+            .fold(0i64, |total, die| total.saturating_add(die.value))
+    }
+}
+
+impl Sides {
+    /// The highest face of the die.
+    fn highest(&self) -> i64 {
+        match self {
+            Self::Number(sides) => *sides as i64,
+            Self::Fudge => 1,
+        }
+    }
+
+    /// Rolls the die once, drawing from the given generator.
+    fn roll<H>(&self, rng: &mut PureRandomGenerator<H>) -> i64
+    where
+        H: Hasher + Default + Clone,
+    {
+        match self {
+            Self::Number(sides) => rng.draw_below(*sides as u64) as i64 + 1,
+            Self::Fudge => rng.draw_below(3) as i64 - 1,
+        }
+    }
+}
+
+impl PureDistribution<i64> for Expr {
+    /// Rolls the expression, returning the total.
+    fn sample<H>(&self, rng: PureRandomGenerator<H>) -> i64
+    where
+        H: Hasher + Default + Clone,
+    {
+        self.roll(rng).total
+    }
+}
+
+impl core::str::FromStr for Expr {
+    type Err = DiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let precedence = self.precedence();
+        let child = |f: &mut Formatter<'_>, expr: &Expr, min: u8| {
+            if expr.precedence() < min {
+                write!(f, "({expr})")
+            } else {
+                write!(f, "{expr}")
+            }
+        };
+
+        match self {
+            Self::Constant(value) => write!(f, "{value}"),
+            Self::Dice(dice) => write!(f, "{dice}"),
+            Self::Neg(expr) => {
+                write!(f, "-")?;
+                child(f, expr, precedence)
+            }
+            Self::Add(a, b) | Self::Sub(a, b) | Self::Mul(a, b) => {
+                let operator = match self {
+                    Self::Add(..) => "+",
+                    Self::Sub(..) => "-",
+                    _ => "*",
+                };
+
+                child(f, a, precedence)?;
+                write!(f, "{operator}")?;
+                child(f, b, precedence + 1)
+            }
+        }
+    }
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.sides {
+            Sides::Number(sides) => write!(f, "{}d{sides}", self.count)?,
+            Sides::Fudge => write!(f, "{}dF", self.count)?,
+        }
+
+        if self.explode {
+            write!(f, "!")?;
+        }
+
+        match self.keep {
+            Keep::All => Ok(()),
+            Keep::Highest(keep) => write!(f, "kh{keep}"),
+            Keep::Lowest(keep) => write!(f, "kl{keep}"),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    fn roll(expr: &str, seed: u64) -> Roll {
+        expr.parse::<Expr>().unwrap().roll(PureRng::new(seed))
+    }
+
+    #[test]
+    fn test_ranges() {
+        for seed in 0..200 {
+            assert!((3..=18).contains(&roll("3d6", seed).total));
+            assert!((5..=20).contains(&roll("3d6+2", seed).total));
+            assert!((1..=100).contains(&roll("d%", seed).total));
+            assert!((-4..=4).contains(&roll("4dF", seed).total));
+            assert!((-6..=-1).contains(&roll("-d6", seed).total));
+            assert!((2..=12).contains(&roll("2*d6", seed).total));
+        }
+    }
+
+    #[test]
+    fn test_modifiers_keep_dice() {
+        for seed in 0..50 {
+            let plain = roll("4d6", seed);
+            let values: Vec<i64> = plain.dice.iter().map(|die| die.value).collect();
+
+            for expr in ["4d6+2", "4d6kh3", "4d6dl1", "4d6kl1", "1+4d6*2"] {
+                let modified = roll(expr, seed);
+                let modified: Vec<i64> = modified.dice.iter().map(|die| die.value).collect();
+                assert_eq!(modified, values, "{expr}");
+            }
+
+            // Adding dice only adds new ones.
+            let more = roll("6d6", seed);
+            assert_eq!(more.dice[..4], plain.dice[..]);
+        }
+    }
+
+    #[test]
+    fn test_keep() {
+        for seed in 0..50 {
+            let roll = roll("4d6kh3", seed);
+            let mut values: Vec<i64> = roll.dice.iter().map(|die| die.value).collect();
+            values.sort();
+
+            assert_eq!(roll.total, values[1..].iter().sum::<i64>());
+            assert_eq!(roll.dice.iter().filter(|die| die.kept).count(), 3);
+        }
+
+        let lowest = roll("2d20kl1", 7);
+        let min = lowest.dice.iter().map(|die| die.value).min().unwrap();
+        assert_eq!(lowest.total, min);
+    }
+
+    #[test]
+    fn test_explode() {
+        let mut exploded = 0;
+
+        for seed in 0..500 {
+            let roll = roll("d4!", seed);
+            let die = roll.dice[0];
+
+            assert_eq!(roll.total, die.value);
+            // Every explosion adds a 4, and the chain ends on a lower face.
+            assert!((1..=3).contains(&(die.value - 4 * die.explosions as i64)));
+            if die.explosions > 0 {
+                exploded += 1;
+            }
+        }
+
+        assert!(exploded > 50);
+    }
+
+    #[test]
+    fn test_terms() {
+        let roll = roll("2d6 + 3 + d8", 1234);
+        let rng = PureRng::new(1234u64);
+
+        assert_eq!(roll.dice.len(), 3);
+        assert_eq!(roll.dice[2].term, 1);
+        assert_eq!(
+            roll.dice[2].value,
+            Sides::Number(8).roll(&mut rng.seed(1u32).seed(0u32)),
+        );
+        assert_eq!(
+            roll.total,
+            roll.dice.iter().map(|die| die.value).sum::<i64>() + 3
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        for (expr, expected) in [
+            ("3d6+2", "3d6+2"),
+            ("d%", "1d100"),
+            ("4d6dl1", "4d6kh3"),
+            ("2d10!k1", "2d10!kh1"),
+            ("4df", "4dF"),
+            ("-(1d4+2)*3", "-(1d4+2)*3"),
+            ("1-(2-3)", "1-(2-3)"),
+            ("(1-2)-3", "1-2-3"),
+        ] {
+            let parsed: Expr = expr.parse().unwrap();
+            assert_eq!(parsed.to_string(), expected);
+            assert_eq!(parsed.to_string().parse::<Expr>(), Ok(parsed));
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! The dice notation parser.
+
+use alloc::boxed::Box;
+use core::fmt::{self, Display, Formatter};
+
+use super::{Dice, Expr, Keep, Sides};
+
+/// The most dice a single term can roll.
+pub const MAX_DICE: u32 = 1000;
+
+/// The deepest brackets and negations can be nested.
+const MAX_DEPTH: u32 = 64;
+
+/// An error parsing a dice expression, or calculating its distribution.
+///
+/// Positions are byte offsets into the expression.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DiceError {
+    /// The expression is empty, or ends part way through.
+    UnexpectedEnd,
+    /// The expression has an unexpected character at the given position.
+    UnexpectedCharacter(usize),
+    /// The number at the given position is larger than `u32::MAX`.
+    NumberTooLarge(usize),
+    /// The dice term at the given position rolls more than [`MAX_DICE`] dice.
+    TooManyDice(usize),
+    /// The die at the given position has no sides.
+    InvalidSides(usize),
+    /// The die at the given position explodes, but only has one side.
+    InvalidExplosion(usize),
+    /// The brackets or negations at the given position are nested too deeply.
+    TooDeep(usize),
+    /// The expression's distribution would take more than
+    /// [`MAX_COMPLEXITY`](super::MAX_COMPLEXITY) steps to calculate.
+    TooComplex,
+}
+
+impl Display for DiceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "dice expression ended unexpectedly"),
+            Self::UnexpectedCharacter(at) => {
+                write!(f, "unexpected character in dice expression at {at}")
+            }
+            Self::NumberTooLarge(at) => write!(f, "number in dice expression at {at} is too large"),
+            Self::TooManyDice(at) => write!(f, "dice term at {at} rolls more than {MAX_DICE} dice"),
+            Self::InvalidSides(at) => write!(f, "die at {at} has no sides"),
+            Self::InvalidExplosion(at) => write!(f, "die at {at} can't explode with one side"),
+            Self::TooDeep(at) => write!(f, "dice expression at {at} is nested too deeply"),
+            Self::TooComplex => write!(f, "dice expression is too complex to calculate"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DiceError {}
+
+/// Parses a complete dice expression.
+pub(super) fn parse(expr: &str) -> Result<Expr, DiceError> {
+    let mut parser = Parser {
+        bytes: expr.as_bytes(),
+        position: 0,
+        depth: 0,
+    };
+
+    let parsed = parser.sum()?;
+    match parser.peek() {
+        None => Ok(parsed),
+        Some(_) => Err(parser.unexpected()),
+    }
+}
+
+/// A recursive descent parser over the bytes of an expression.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    depth: u32,
+}
+
+impl Parser<'_> {
+    /// Returns the next byte that isn't whitespace, without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(byte) = self.bytes.get(self.position) {
+            if !byte.is_ascii_whitespace() {
+                return Some(*byte);
+            }
+            self.position += 1;
+        }
+
+        None
+    }
+
+    /// The error for the byte at the current position not fitting the
+    /// grammar.
+    fn unexpected(&self) -> DiceError {
+        if self.position < self.bytes.len() {
+            DiceError::UnexpectedCharacter(self.position)
+        } else {
+            DiceError::UnexpectedEnd
+        }
+    }
+
+    /// Consumes the next byte if it matches, ignoring case.
+    fn eat(&mut self, expected: u8) -> bool {
+        let matches = self
+            .bytes
+            .get(self.position)
+            .is_some_and(|byte| byte.eq_ignore_ascii_case(&expected));
+        if matches {
+            self.position += 1;
+        }
+
+        matches
+    }
+
+    /// `sum := product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<Expr, DiceError> {
+        let mut expr = self.product()?;
+
+        loop {
+            match self.peek() {
+                Some(b'+') => {
+                    self.position += 1;
+                    expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+                }
+                Some(b'-') => {
+                    self.position += 1;
+                    expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// `product := unary ('*' unary)*`
+    fn product(&mut self) -> Result<Expr, DiceError> {
+        let mut expr = self.unary()?;
+
+        while let Some(b'*') = self.peek() {
+            self.position += 1;
+            expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+        }
+
+        Ok(expr)
+    }
+
+    /// `unary := '-' unary | '(' sum ')' | dice | number`
+    fn unary(&mut self) -> Result<Expr, DiceError> {
+        let start = match self.peek() {
+            Some(_) => self.position,
+            None => return Err(DiceError::UnexpectedEnd),
+        };
+
+        if self.eat(b'-') || self.eat(b'(') {
+            self.depth += 1;
+            if self.depth > MAX_DEPTH {
+                return Err(DiceError::TooDeep(start));
+            }
+
+            let expr = if self.bytes[start] == b'-' {
+                Expr::Neg(Box::new(self.unary()?))
+            } else {
+                let expr = self.sum()?;
+                if self.peek() != Some(b')') {
+                    return Err(self.unexpected());
+                }
+                self.position += 1;
+                expr
+            };
+
+            self.depth -= 1;
+            return Ok(expr);
+        }
+
+        let count = self.number()?;
+        if !self.eat(b'd') {
+            return match count {
+                Some(value) => Ok(Expr::Constant(value.into())),
+                None => Err(DiceError::UnexpectedCharacter(start)),
+            };
+        }
+
+        self.dice(start, count.unwrap_or(1)).map(Expr::Dice)
+    }
+
+    /// Parses the rest of a dice term, after the `d`.
+    fn dice(&mut self, start: usize, count: u32) -> Result<Dice, DiceError> {
+        if count > MAX_DICE {
+            return Err(DiceError::TooManyDice(start));
+        }
+
+        let sides = if self.eat(b'%') {
+            Sides::Number(100)
+        } else if self.eat(b'f') {
+            Sides::Fudge
+        } else {
+            match self.number()? {
+                Some(0) => return Err(DiceError::InvalidSides(start)),
+                Some(sides) => Sides::Number(sides),
+                None => return Err(self.unexpected()),
+            }
+        };
+
+        let mut dice = Dice {
+            count,
+            sides,
+            keep: Keep::All,
+            explode: false,
+        };
+
+        // Modifiers may come in either order, but only once each.
+        loop {
+            let position = self.position;
+
+            if !dice.explode && self.eat(b'!') {
+                if sides == Sides::Number(1) {
+                    return Err(DiceError::InvalidExplosion(start));
+                }
+                dice.explode = true;
+            } else if dice.keep == Keep::All && (self.eat(b'k') || self.eat(b'd')) {
+                let keep = self.bytes[position].eq_ignore_ascii_case(&b'k');
+                let highest = if self.eat(b'h') {
+                    true
+                } else if self.eat(b'l') {
+                    false
+                } else if keep {
+                    true
+                } else {
+                    return Err(self.unexpected());
+                };
+
+                let n = match self.number()? {
+                    Some(n) => n,
+                    None => return Err(self.unexpected()),
+                };
+
+                // Dropping the highest dice keeps the lowest, and vice versa.
+                dice.keep = match (keep, highest) {
+                    (true, true) => Keep::Highest(n),
+                    (true, false) => Keep::Lowest(n),
+                    (false, true) => Keep::Lowest(count.saturating_sub(n)),
+                    (false, false) => Keep::Highest(count.saturating_sub(n)),
+                };
+            } else {
+                return Ok(dice);
+            }
+        }
+    }
+
+    /// Parses an unsigned number, if there is one at the current position.
+    fn number(&mut self) -> Result<Option<u32>, DiceError> {
+        let start = self.position;
+        let mut value: u32 = 0;
+
+        while let Some(digit) = self.bytes.get(self.position).filter(|b| b.is_ascii_digit()) {
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add(u32::from(digit - b'0')))
+                .ok_or(DiceError::NumberTooLarge(start))?;
+            self.position += 1;
+        }
+
+        Ok((self.position > start).then_some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dice(count: u32, sides: u32, keep: Keep, explode: bool) -> Expr {
+        Expr::Dice(Dice {
+            count,
+            sides: Sides::Number(sides),
+            keep,
+            explode,
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("3d6+2"),
+            Ok(Expr::Add(
+                Box::new(dice(3, 6, Keep::All, false)),
+                Box::new(Expr::Constant(2)),
+            ))
+        );
+        assert_eq!(parse(" D20 "), Ok(dice(1, 20, Keep::All, false)));
+        assert_eq!(parse("d%"), Ok(dice(1, 100, Keep::All, false)));
+        assert_eq!(parse("4d6kh3"), Ok(dice(4, 6, Keep::Highest(3), false)));
+        assert_eq!(parse("4d6k3"), Ok(dice(4, 6, Keep::Highest(3), false)));
+        assert_eq!(parse("4d6dl1"), Ok(dice(4, 6, Keep::Highest(3), false)));
+        assert_eq!(parse("2d20dh1"), Ok(dice(2, 20, Keep::Lowest(1), false)));
+        assert_eq!(parse("2d6!kl1"), Ok(dice(2, 6, Keep::Lowest(1), true)));
+        assert_eq!(parse("2d6kl1!"), Ok(dice(2, 6, Keep::Lowest(1), true)));
+        assert_eq!(
+            parse("4dF"),
+            Ok(Expr::Dice(Dice {
+                count: 4,
+                sides: Sides::Fudge,
+                keep: Keep::All,
+                explode: false,
+            }))
+        );
+        assert_eq!(
+            parse("2*3-1"),
+            Ok(Expr::Sub(
+                Box::new(Expr::Mul(
+                    Box::new(Expr::Constant(2)),
+                    Box::new(Expr::Constant(3)),
+                )),
+                Box::new(Expr::Constant(1)),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse(""), Err(DiceError::UnexpectedEnd));
+        assert_eq!(parse("3d6+"), Err(DiceError::UnexpectedEnd));
+        assert_eq!(parse("3d"), Err(DiceError::UnexpectedEnd));
+        assert_eq!(parse("(3d6"), Err(DiceError::UnexpectedEnd));
+        assert_eq!(parse("3d6 x"), Err(DiceError::UnexpectedCharacter(4)));
+        assert_eq!(parse("3d6!!"), Err(DiceError::UnexpectedCharacter(4)));
+        assert_eq!(parse("4d6kh3kl1"), Err(DiceError::UnexpectedCharacter(6)));
+        assert_eq!(parse("4d6kx"), Err(DiceError::UnexpectedCharacter(4)));
+        assert_eq!(parse("4d6d"), Err(DiceError::UnexpectedEnd));
+        assert_eq!(parse("3d0"), Err(DiceError::InvalidSides(0)));
+        assert_eq!(parse("1+d1!"), Err(DiceError::InvalidExplosion(2)));
+        assert_eq!(parse("1001d6"), Err(DiceError::TooManyDice(0)));
+        assert_eq!(parse("99999999999"), Err(DiceError::NumberTooLarge(0)));
+        assert_eq!(
+            parse(&"(".repeat(100)),
+            Err(DiceError::TooDeep(MAX_DEPTH as usize))
+        );
+    }
+}
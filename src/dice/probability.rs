@@ -0,0 +1,362 @@
+//! Exact probability distributions of dice expressions.
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use super::{Dice, DiceError, Keep, Sides, MAX_COMPLEXITY, MAX_EXPLOSIONS};
+
+/// Exploding chains less likely than this are cut off.
+const NEGLIGIBLE: f64 = 1e-20;
+
+/// The work left before a distribution is too complex to calculate, counted
+/// in the same steps as [`MAX_COMPLEXITY`].
+pub(super) struct Budget(u64);
+
+impl Budget {
+    pub(super) fn new() -> Self {
+        Self(MAX_COMPLEXITY)
+    }
+
+    /// Takes `steps` from the budget before doing that much work, failing if
+    /// not enough are left.
+    fn spend(&mut self, steps: u64) -> Result<(), DiceError> {
+        self.0 = self.0.checked_sub(steps).ok_or(DiceError::TooComplex)?;
+        Ok(())
+    }
+}
+
+/// The probability of each possible total of a dice expression, from
+/// [`Expr::distribution`](super::Expr::distribution).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probabilities {
+    outcomes: BTreeMap<i64, f64>,
+}
+
+impl Probabilities {
+    /// A distribution that is always the given value.
+    pub(super) fn constant(value: i64) -> Self {
+        Self {
+            outcomes: BTreeMap::from([(value, 1.0)]),
+        }
+    }
+
+    /// The probability of the total being exactly `value`.
+    pub fn probability(&self, value: i64) -> f64 {
+        self.outcomes.get(&value).copied().unwrap_or(0.0)
+    }
+
+    /// The probability of the total being `value` or more.
+    pub fn at_least(&self, value: i64) -> f64 {
+        self.outcomes.range(value..).map(|(_, p)| p).sum()
+    }
+
+    /// The probability of the total being `value` or less.
+    pub fn at_most(&self, value: i64) -> f64 {
+        self.outcomes.range(..=value).map(|(_, p)| p).sum()
+    }
+
+    /// The lowest possible total.
+    pub fn min(&self) -> i64 {
+        *self.outcomes.keys().next().unwrap()
+    }
+
+    /// The highest possible total.
+    pub fn max(&self) -> i64 {
+        *self.outcomes.keys().next_back().unwrap()
+    }
+
+    /// The expected total.
+    pub fn mean(&self) -> f64 {
+        self.outcomes.iter().map(|(&v, p)| v as f64 * p).sum()
+    }
+
+    /// Iterates over the possible totals and their probabilities, from lowest
+    /// to highest.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.outcomes.iter().map(|(&v, &p)| (v, p))
+    }
+
+    /// Applies a function to every total.
+    pub(super) fn map(&self, f: impl Fn(i64) -> i64) -> Self {
+        let mut outcomes = BTreeMap::new();
+        for (&value, &p) in &self.outcomes {
+            *outcomes.entry(f(value)).or_insert(0.0) += p;
+        }
+
+        Self { outcomes }
+    }
+
+    /// The distribution of `f(a, b)` for independent `a` and `b`.
+    pub(super) fn combine(
+        &self,
+        other: &Self,
+        budget: &mut Budget,
+        f: impl Fn(i64, i64) -> i64,
+    ) -> Result<Self, DiceError> {
+        budget.spend((self.outcomes.len() as u64).saturating_mul(other.outcomes.len() as u64))?;
+        Ok(self.convolve(other, f))
+    }
+
+    /// [`combine`](Self::combine), leaving the caller to account for the
+    /// work.
+    fn convolve(&self, other: &Self, f: impl Fn(i64, i64) -> i64) -> Self {
+        let mut outcomes = BTreeMap::new();
+        for (&a, &p) in &self.outcomes {
+            for (&b, &q) in &other.outcomes {
+                *outcomes.entry(f(a, b)).or_insert(0.0) += p * q;
+            }
+        }
+
+        Self { outcomes }
+    }
+}
+
+/// The distribution of a dice term.
+pub(super) fn dice(dice: &Dice, budget: &mut Budget) -> Result<Probabilities, DiceError> {
+    let die = die(dice.sides, dice.explode, budget)?;
+
+    let keep = match dice.keep {
+        Keep::Highest(keep) | Keep::Lowest(keep) if keep < dice.count => keep,
+        _ => {
+            // The `k`th sum has at most `k (n - 1) + 1` totals for `n` faces,
+            // so charge for the whole sum up front rather than fail part way.
+            let count = u64::from(dice.count);
+            let faces = die.len() as u64;
+            let pairs = count.saturating_mul(count.saturating_sub(1)) / 2;
+            budget.spend(
+                faces.saturating_mul(count.saturating_add(pairs.saturating_mul(faces - 1))),
+            )?;
+
+            let die = Probabilities {
+                outcomes: die.into_iter().collect(),
+            };
+            return Ok(
+                (0..dice.count).fold(Probabilities::constant(0), |total, _| {
+                    total.convolve(&die, i64::saturating_add)
+                }),
+            );
+        }
+    };
+
+    let mut faces = die;
+    if let Keep::Highest(_) = dice.keep {
+        faces.reverse();
+    }
+
+    Ok(Probabilities {
+        outcomes: kept(&faces, dice.count, keep, budget)?,
+    })
+}
+
+/// The distribution of a single die, in order of value.
+fn die(sides: Sides, explode: bool, budget: &mut Budget) -> Result<Vec<(i64, f64)>, DiceError> {
+    let (lowest, highest) = match sides {
+        Sides::Number(sides) => (1, i64::from(sides)),
+        Sides::Fudge => (-1, 1),
+    };
+    let faces = highest - lowest + 1;
+    let p = 1.0 / faces as f64;
+
+    if !explode {
+        budget.spend(faces as u64)?;
+        return Ok((lowest..=highest).map(|value| (value, p)).collect());
+    }
+
+    // Each link of the chain adds the highest face, until a lower face ends
+    // it. The last link is taken as final, whatever it rolls.
+    let mut outcomes = Vec::new();
+    let mut reached = 1.0;
+    let mut base = 0;
+
+    for explosions in 0..=MAX_EXPLOSIONS {
+        let last = explosions == MAX_EXPLOSIONS || reached * p < NEGLIGIBLE;
+        let end = if last { highest } else { highest - 1 };
+
+        budget.spend((end - lowest + 1) as u64)?;
+        outcomes.extend((lowest..=end).map(|value| (base + value, reached * p)));
+
+        if last {
+            break;
+        }
+        reached *= p;
+        base += highest;
+    }
+
+    // Fudge dice can roll lower totals after exploding, so restore the order.
+    outcomes.sort_by_key(|&(value, _)| value);
+    let mut merged: Vec<(i64, f64)> = Vec::with_capacity(outcomes.len());
+    for (value, p) in outcomes {
+        match merged.last_mut() {
+            Some((last, q)) if *last == value => *q += p,
+            _ => merged.push((value, p)),
+        }
+    }
+
+    Ok(merged)
+}
+
+/// The distribution of the sum of the first `keep` of `count` dice, once
+/// they're sorted into the order of `faces`.
+///
+/// Works through the faces in order, tracking how many dice have been
+/// assigned a face so far and the sum of those that are kept. Assigning `j`
+/// of the `m` remaining dice to a face has probability `C(m, j) p^j`.
+fn kept(
+    faces: &[(i64, f64)],
+    count: u32,
+    keep: u32,
+    budget: &mut Budget,
+) -> Result<BTreeMap<i64, f64>, DiceError> {
+    let count = count as usize;
+    let keep = keep as usize;
+
+    // The probability of each kept sum, indexed by the number assigned.
+    let mut states = vec![BTreeMap::new(); count + 1];
+    states[0].insert(0i64, 1.0);
+
+    for &(value, p) in faces {
+        let steps = states
+            .iter()
+            .enumerate()
+            .fold(0u64, |steps, (assigned, sums)| {
+                let choices = (count - assigned + 1) as u64;
+                steps.saturating_add(choices.saturating_mul(sums.len().max(1) as u64))
+            });
+        budget.spend(steps)?;
+
+        let mut next = vec![BTreeMap::new(); count + 1];
+
+        for (assigned, sums) in states.iter().enumerate() {
+            let remaining = count - assigned;
+            let mut weight = 1.0;
+
+            for j in 0..=remaining {
+                let added = (assigned + j).min(keep) - assigned.min(keep);
+                let offset = value.saturating_mul(added as i64);
+
+                for (&sum, &q) in sums {
+                    *next[assigned + j]
+                        .entry(sum.saturating_add(offset))
+                        .or_insert(0.0) += q * weight;
+                }
+
+                // Move on to C(m, j + 1) p^(j + 1).
+                weight *= (remaining - j) as f64 / (j + 1) as f64 * p;
+            }
+        }
+
+        states = next;
+    }
+
+    Ok(states.pop().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Expr;
+
+    fn distribution(expr: &str) -> super::Probabilities {
+        expr.parse::<Expr>().unwrap().distribution().unwrap()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_sums() {
+        let dist = distribution("3d6+2");
+        assert_eq!((dist.min(), dist.max()), (5, 20));
+        assert_close(dist.probability(12), 27.0 / 216.0);
+        assert_close(dist.mean(), 12.5);
+        assert_close(dist.iter().map(|(_, p)| p).sum(), 1.0);
+
+        let dist = distribution("d% - 2*d4");
+        assert_eq!((dist.min(), dist.max()), (-7, 98));
+        assert_close(dist.mean(), 50.5 - 5.0);
+
+        let dist = distribution("4dF");
+        assert_eq!((dist.min(), dist.max()), (-4, 4));
+        assert_close(dist.probability(4), 1.0 / 81.0);
+    }
+
+    #[test]
+    fn test_keep() {
+        let dist = distribution("4d6kh3");
+        assert_eq!((dist.min(), dist.max()), (3, 18));
+        assert_close(dist.probability(18), 21.0 / 1296.0);
+        assert_close(dist.probability(3), 1.0 / 1296.0);
+        assert_close(dist.mean(), 15869.0 / 1296.0);
+
+        // Advantage and disadvantage.
+        let dist = distribution("2d20kh1");
+        assert_close(dist.probability(20), 39.0 / 400.0);
+        let dist = distribution("2d20dh1");
+        assert_close(dist.probability(20), 1.0 / 400.0);
+        assert_close(dist.at_most(10), 1.0 - 100.0 / 400.0);
+    }
+
+    #[test]
+    fn test_explode() {
+        let dist = distribution("d6!");
+        assert_eq!(dist.probability(6), 0.0);
+        assert_close(dist.probability(5), 1.0 / 6.0);
+        assert_close(dist.probability(8), 1.0 / 36.0);
+        assert_close(dist.mean(), 3.5 * 6.0 / 5.0);
+        assert_close(dist.iter().map(|(_, p)| p).sum(), 1.0);
+
+        let dist = distribution("dF!");
+        assert_close(dist.probability(0), 1.0 / 3.0 + 1.0 / 9.0);
+        assert_close(dist.mean(), 0.0);
+    }
+
+    #[test]
+    fn test_too_complex() {
+        use super::super::DiceError;
+
+        for expr in [
+            "1000d1000",
+            "1000d6!",
+            "1000d1000kh500",
+            "d4000000000",
+            "d100000*d100000",
+        ] {
+            let parsed: Expr = expr.parse().unwrap();
+            assert_eq!(parsed.distribution(), Err(DiceError::TooComplex), "{expr}");
+        }
+
+        // Large but reasonable expressions are still fine.
+        assert_close(distribution("200d6").mean(), 700.0);
+        assert_close(distribution("20d100").mean(), 1010.0);
+        assert_eq!(distribution("40d20kh20").max(), 400);
+    }
+
+    #[cfg(feature = "rapidhash")]
+    #[test]
+    fn test_matches_rolls() {
+        use crate::PureRng;
+
+        const SAMPLES: u32 = 20_000;
+
+        for expr in ["4d6kh3", "3d4!kl2", "2d6*d4-3", "4dF"] {
+            let parsed: Expr = expr.parse().unwrap();
+            let dist = parsed.distribution().unwrap();
+            let rng = PureRng::new(expr);
+
+            let mean = (0..SAMPLES)
+                .map(|i| {
+                    let total = parsed.roll(rng.seed(i)).total;
+                    assert!(dist.probability(total) > 0.0, "{expr} rolled {total}");
+                    total as f64
+                })
+                .sum::<f64>()
+                / SAMPLES as f64;
+            // Allow five standard errors.
+            let variance: f64 = dist
+                .iter()
+                .map(|(v, p)| (v as f64 - dist.mean()).powi(2) * p)
+                .sum();
+            let tolerance = 5.0 * (variance / SAMPLES as f64).sqrt();
+            assert!((mean - dist.mean()).abs() < tolerance, "{expr}: {mean}");
+        }
+    }
+}
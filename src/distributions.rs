@@ -0,0 +1,110 @@
+//! Distributions which receive a [`PureRandomGenerator`] by value.
+//!
+//! A `rand` `Distribution` only ever sees an `&mut R: Rng`, so it has no way
+//! to call [`PureRandomGenerator::seed`] and derive labelled sub-values. A
+//! [`PureDistribution`] is handed the generator itself, which makes it possible
+//! to write compound generators as reusable types where every field has its
+//! own stable seed.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+//! # {
+//! use core::hash::Hasher;
+//!
+//! use pure_rng::{distributions::PureDistribution, PureRandomGenerator, PureRng};
+//!
+//! struct Stats { health: i32, damage: i32 }
+//!
+//! struct StatBlock { level: i32 }
+//!
+//! impl PureDistribution<Stats> for StatBlock {
+//!     fn sample<H>(&self, rng: PureRandomGenerator<H>) -> Stats
+//!     where
+//!         H: Hasher + Default + Clone,
+//!     {
+//!         Stats {
+//!             health: rng.seed("health").gen_range(10..20) * self.level,
+//!             damage: rng.seed("damage").gen_range(1..4) * self.level,
+//!         }
+//!     }
+//! }
+//!
+//! let stats: Stats = PureRng::new(1234).sample_pure(StatBlock { level: 3 });
+//! # }
+//! ```
+
+use core::hash::Hasher;
+
+use crate::PureRandomGenerator;
+
+#[cfg(feature = "rand_08")]
+pub use crate::rand_08::distributions::{FromDistribution, IntoDistribution};
+#[cfg(all(feature = "rand_09", not(feature = "rand_08")))]
+pub use crate::rand_09::distributions::{FromDistribution, IntoDistribution};
+
+/// Types that can produce a `T` from a [`PureRandomGenerator`], consuming it.
+///
+/// This is the pure counterpart to `rand`'s `Distribution` trait. Implementations are free
+/// to call [`PureRandomGenerator::seed`] on the generator they receive.
+pub trait PureDistribution<T> {
+    /// Generate a value from the given generator.
+    fn sample<H>(&self, rng: PureRandomGenerator<H>) -> T
+    where
+        H: Hasher + Default + Clone;
+}
+
+impl<T, D> PureDistribution<T> for &D
+where
+    D: PureDistribution<T> + ?Sized,
+{
+    fn sample<H>(&self, rng: PureRandomGenerator<H>) -> T
+    where
+        H: Hasher + Default + Clone,
+    {
+        (*self).sample(rng)
+    }
+}
+
+/// Types that can generate themselves from a [`PureRandomGenerator`].
+///
+/// This can be derived for structs with the `derive` feature. Each field is
+/// generated from the generator seeded with the field's name, so reordering
+/// the fields doesn't change their values. Fields are sampled from the
+/// `Standard` distribution by default, which can be changed with the `pure`
+/// attribute:
+///
+/// ```
+/// # #[cfg(all(feature = "rapidhash", feature = "derive", feature = "rand_08"))]
+/// # {
+/// use pure_rng::{PureGenerate, PureRng};
+/// use rand_distr::Normal;
+///
+/// #[derive(PureGenerate)]
+/// struct Monster {
+///     #[pure(dist = Normal::new(20., 6.).unwrap())]
+///     health: f32,
+///     #[pure(range = 1..10)]
+///     damage: i32,
+///     // Keeps the values of a renamed field.
+///     #[pure(seed = "hit chance")]
+///     accuracy: f64,
+///     // Delegates to the field type's own `PureGenerate` implementation.
+///     #[pure(generate)]
+///     loot: Loot,
+///     is_boss: bool,
+/// }
+///
+/// #[derive(PureGenerate)]
+/// struct Loot(u8, u8);
+///
+/// let monster = Monster::generate(PureRng::new(1234));
+/// # }
+/// ```
+pub trait PureGenerate: Sized {
+    /// Generate a value from the given generator.
+    fn generate<H>(rng: PureRandomGenerator<H>) -> Self
+    where
+        H: Hasher + Default + Clone;
+}
@@ -0,0 +1,197 @@
+//! Hasher adapters.
+
+use core::hash::Hasher;
+
+/// Wraps a [`Hasher`], making the hashing of integers independent of the
+/// platform's byte order and pointer width.
+///
+/// The default [`Hasher`] integer methods write the value's native-endian
+/// bytes, so hashing the same seed on a big-endian platform gives a different
+/// result. Similarly `usize` and `isize` are 4 bytes wide on 32-bit platforms
+/// like WASM, and 8 bytes on 64-bit desktops. `StableHasher` forwards every
+/// integer to the wrapped hasher as little-endian bytes, and widens `usize` and
+/// `isize` to 64 bits first, so that eg. `rng.seed(some_usize)` gives identical
+/// results everywhere.
+///
+/// On little-endian platforms this matches what most hashers do already, so
+/// wrapping a hasher only changes the hashes of `usize` and `isize` on 32-bit
+/// platforms.
+///
+/// Note that this only normalises integers passed to the hasher's integer
+/// methods. The [`Hash`](core::hash::Hash) implementation of the seed value
+/// must itself be platform-independent. In particular, slices, arrays and
+/// `Vec`s of integers hash their elements as one block of native-endian bytes
+/// through [`write`](Hasher::write), which this can't normalise, so
+/// `rng.seed([1usize, 2, 3])` still differs between platforms. Seed with each
+/// element in turn instead, as in the example below. Strings, bytes and single
+/// integers, and types deriving `Hash` from them, are unaffected.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rand_08")]
+/// # {
+/// use pure_rng::{PureRandomGenerator, StableHasher};
+///
+/// type MyRng = PureRandomGenerator<StableHasher<std::collections::hash_map::DefaultHasher>>;
+///
+/// let value: u64 = MyRng::new(1234).seed(7usize).gen();
+///
+/// // Seeding with a slice of integers isn't portable, but seeding with its
+/// // elements one at a time is.
+/// let path = [3usize, 1, 4];
+/// let rng = path.iter().fold(MyRng::new(1234), |rng, &step| rng.seed(step));
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StableHasher<H>(H);
+
+impl<H> StableHasher<H> {
+    /// Wraps the given hasher.
+    pub fn new(hasher: H) -> Self {
+        Self(hasher)
+    }
+
+    /// Returns the wrapped hasher.
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
+
+impl<H: Hasher> Hasher for StableHasher<H> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.0.write_u8(i)
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.0.write(&i.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.0.write(&i.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.0.write(&i.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.0.write(&i.to_le_bytes())
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64)
+    }
+}
+
+/// A [`Hasher`] which can also finish with a 128-bit hash.
+///
+/// Implemented for the hashers behind the `siphash` and `xxh3` features, and
+/// required by [`PureRandomGenerator128`](crate::PureRandomGenerator128).
+/// Like [`Hasher::finish`], `finish128` must not reset the hasher, so that it
+/// can carry on being written to afterwards.
+pub trait Hasher128: Hasher {
+    /// Returns the 128-bit hash of the values written so far.
+    fn finish128(&self) -> u128;
+}
+
+impl<H: Hasher128> Hasher128 for StableHasher<H> {
+    #[inline]
+    fn finish128(&self) -> u128 {
+        self.0.finish128()
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use core::hash::Hash;
+
+    use rapidhash::RapidHasher;
+
+    use super::*;
+
+    fn hash(value: impl Hash) -> u64 {
+        let mut hasher = StableHasher::<RapidHasher>::default();
+        value.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        let mut hasher = RapidHasher::default();
+        hasher.write(bytes);
+
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_little_endian() {
+        assert_eq!(hash(0x0102u16), hash_bytes(&[2, 1]));
+        assert_eq!(hash(-2i32), hash_bytes(&[0xfe, 0xff, 0xff, 0xff]));
+        assert_eq!(hash(1u64), hash_bytes(&[1, 0, 0, 0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_pointer_width() {
+        assert_eq!(hash(12345usize), hash(12345u64));
+        assert_eq!(hash(-12345isize), hash(-12345i64));
+
+        // Elements hashed one at a time are widened too, unlike a slice.
+        let mut hasher = StableHasher::<RapidHasher>::default();
+        for value in [1usize, 2, 3] {
+            hasher.write_usize(value);
+        }
+        let mut expected = RapidHasher::default();
+        for value in [1u64, 2, 3] {
+            expected.write(&value.to_le_bytes());
+        }
+        assert_eq!(hasher.finish(), expected.finish());
+    }
+}
@@ -0,0 +1,230 @@
+//! Ready-made generators using hashers other than the default rapidhash.
+//!
+//! Each backend is enabled by the feature of the same name, except
+//! [`SplitMix64Hasher`], which is implemented here and always available. The
+//! generators all wrap their hasher in a [`StableHasher`], so that integers
+//! are hashed identically regardless of byte order and pointer width.
+//!
+//! | Generator           | Feature   | Speed    | Quality                         |
+//! |---------------------|-----------|----------|---------------------------------|
+//! | [`PureRng`]         | rapidhash | fast     | good, passes PractRand to 32 TB |
+//! | [`SipPureRng`]      | siphash   | slow     | excellent                       |
+//! | [`WyPureRng`]       | wyhash    | fast     | good                            |
+//! | [`Xxh3PureRng`]     | xxh3      | slowest  | good                            |
+//! | [`FxPureRng`]       | fxhash    | fastest  | poor                            |
+//! | [`FoldPureRng`]     | foldhash  | fastest  | good                            |
+//! | [`SplitMixPureRng`] |           | moderate | good                            |
+//!
+//! Some notes on the trade-offs:
+//!
+//! * SipHash-1-3 is the algorithm behind the standard library's
+//!   `DefaultHasher`. It's designed to resist attacks on hash tables, at the
+//!   cost of doing more mixing than a generator needs.
+//! * xxh3 is very fast for long inputs, but its streaming hasher carries a
+//!   buffer of several hundred bytes, which has to be copied by every `seed()`
+//!   call. It suits generators which draw many values per seed.
+//! * FxHash is the hasher used inside `rustc`. It does little more than a
+//!   multiply per write, so sequences of values drawn from it are noticeably
+//!   patterned. It's fine for picking between a handful of options in a hot
+//!   loop, but not for anything statistical. Its state is the width of a
+//!   `usize`, so unlike the others it gives different values on 32-bit and
+//!   64-bit platforms.
+//! * foldhash is used with a fixed seed, and its `quality` variant. The
+//!   foldhash crate makes no promise that its output stays the same between
+//!   versions, so values may change when upgrading it.
+//! * [`SplitMix64Hasher`] feeds the input through the SplitMix64 finaliser in
+//!   8 byte words. It has the smallest state and no dependencies.
+//!
+//! [`SipPureRng128`] and [`Xxh3PureRng128`] are [`PureRandomGenerator128`]s,
+//! which keep 128 bits of state between draws using the 128-bit versions of
+//! the same hashes.
+//!
+//! Only the default [`PureRng`] has been tested with PractRand. The ratings
+//! above reflect the published analysis of the hash functions themselves, and
+//! the speeds are a rough ordering rather than measurements; benchmark with
+//! your own seeds and draw counts if it matters.
+//!
+//! [`PureRng`]: crate::PureRng
+//! [`PureRandomGenerator128`]: crate::PureRandomGenerator128
+
+use core::hash::Hasher;
+
+#[cfg(any(feature = "siphash", feature = "xxh3"))]
+use crate::{Hasher128, PureRandomGenerator128};
+use crate::{PureRandomGenerator, StableHasher};
+
+/// A generator using SipHash-1-3 with a zero key.
+#[cfg(feature = "siphash")]
+pub type SipPureRng = PureRandomGenerator<StableHasher<siphasher::sip::SipHasher13>>;
+
+/// A generator using wyhash with a zero seed.
+#[cfg(feature = "wyhash")]
+pub type WyPureRng = PureRandomGenerator<StableHasher<wyhash::WyHash>>;
+
+/// A generator using the 64-bit xxh3 hash with the default secret.
+#[cfg(feature = "xxh3")]
+pub type Xxh3PureRng = PureRandomGenerator<StableHasher<xxhash_rust::xxh3::Xxh3Default>>;
+
+/// A generator using FxHash, the hasher used inside `rustc`.
+#[cfg(feature = "fxhash")]
+pub type FxPureRng = PureRandomGenerator<StableHasher<rustc_hash::FxHasher>>;
+
+/// A generator using foldhash with a fixed seed.
+#[cfg(feature = "foldhash")]
+pub type FoldPureRng = PureRandomGenerator<StableHasher<FoldHasher>>;
+
+/// A generator using 128-bit SipHash-1-3 with a zero key, which carries 128
+/// bits of state between draws.
+#[cfg(feature = "siphash")]
+pub type SipPureRng128 = PureRandomGenerator128<StableHasher<siphasher::sip128::SipHasher13>>;
+
+/// A generator using the 128-bit xxh3 hash with the default secret, which
+/// carries 128 bits of state between draws.
+#[cfg(feature = "xxh3")]
+pub type Xxh3PureRng128 = PureRandomGenerator128<StableHasher<xxhash_rust::xxh3::Xxh3Default>>;
+
+/// A generator using [`SplitMix64Hasher`].
+pub type SplitMixPureRng = PureRandomGenerator<StableHasher<SplitMix64Hasher>>;
+
+#[cfg(feature = "siphash")]
+impl Hasher128 for siphasher::sip128::SipHasher13 {
+    #[inline]
+    fn finish128(&self) -> u128 {
+        siphasher::sip128::Hasher128::finish128(self).into()
+    }
+}
+
+#[cfg(feature = "xxh3")]
+impl Hasher128 for xxhash_rust::xxh3::Xxh3Default {
+    #[inline]
+    fn finish128(&self) -> u128 {
+        self.digest128()
+    }
+}
+
+/// The `quality` variant of foldhash, with a fixed seed so that it can
+/// implement [`Default`].
+#[cfg(feature = "foldhash")]
+#[derive(Clone)]
+pub struct FoldHasher(foldhash::quality::FoldHasher<'static>);
+
+#[cfg(feature = "foldhash")]
+impl Default for FoldHasher {
+    fn default() -> Self {
+        use core::hash::BuildHasher;
+
+        Self(foldhash::quality::FixedState::default().build_hasher())
+    }
+}
+
+#[cfg(feature = "foldhash")]
+impl core::fmt::Debug for FoldHasher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FoldHasher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "foldhash")]
+impl Hasher for FoldHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+}
+
+/// The golden ratio increment used by SplitMix64.
+const GOLDEN_GAMMA: u64 = 0x9e3779b97f4a7c15;
+
+/// A hasher built on the SplitMix64 generator, in the spirit of the "Don't
+/// generate, hash!" talk.
+///
+/// The input is split into little-endian 8 byte words. For each word the state
+/// advances by SplitMix64's golden gamma once per byte, as SplitMix64 does once
+/// per output, and the word is then mixed in with the SplitMix64 finaliser.
+/// Finishing mixes the state once more, so that a new hasher finishes with the
+/// first output of SplitMix64 seeded with zero.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitMix64Hasher {
+    state: u64,
+}
+
+impl SplitMix64Hasher {
+    /// Creates a hasher with the given initial state.
+    pub const fn with_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Hasher for SplitMix64Hasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        mix(self.state.wrapping_add(GOLDEN_GAMMA))
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+
+            let advanced = self
+                .state
+                .wrapping_add(GOLDEN_GAMMA.wrapping_mul(chunk.len() as u64));
+            self.state = mix(advanced ^ u64::from_le_bytes(word));
+        }
+    }
+}
+
+/// The SplitMix64 finaliser, a variant of MurmurHash3's `fmix64`.
+#[inline]
+pub(crate) fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(bytes: &[u8]) -> u64 {
+        let mut hasher = SplitMix64Hasher::default();
+        hasher.write(bytes);
+
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_splitmix_reference() {
+        // The first output of the reference SplitMix64 seeded with zero.
+        assert_eq!(SplitMix64Hasher::default().finish(), 0xe220a8397b1dcdaf);
+        // And the second, as seeding with the first gamma then finishing is
+        // equivalent to advancing it twice.
+        assert_eq!(
+            SplitMix64Hasher::with_seed(GOLDEN_GAMMA).finish(),
+            0x6e789e6aa1b965f4
+        );
+    }
+
+    #[test]
+    fn test_splitmix_known_answers() {
+        assert_eq!(hash(b""), 0xe220a8397b1dcdaf);
+        assert_eq!(hash(&[0]), 0xa706dd2f4d197e6f);
+        assert_eq!(hash(&[0; 8]), 0xea5787965e2c85f2);
+        assert_eq!(hash(b"pure_rng"), 0x0320cd92bb4841f4);
+        assert_eq!(hash(b"Don't generate, hash!"), 0x6ddd32ac0e02feff);
+    }
+
+    #[test]
+    fn test_splitmix_lengths_differ() {
+        assert_ne!(hash(&[1]), hash(&[1, 0]));
+        assert_ne!(hash(&[0; 8]), hash(&[0; 7]));
+        assert_ne!(hash(&[0; 16]), hash(&[0; 8]));
+    }
+}
@@ -0,0 +1,150 @@
+//! Seed labels hashed at compile time.
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
+/// A string label which has been hashed ahead of time, for use with
+/// [`PureRandomGenerator::seed_label`](crate::PureRandomGenerator::seed_label).
+///
+/// Seeding with a `&str` hashes the whole string every time, which adds up in
+/// hot loops. A `SeedLabel` hashes its string once, in a `const` context if
+/// created with the [`seed_label!`](crate::seed_label) macro, so that seeding
+/// with it costs a single `write_u64`.
+///
+/// The label is hashed independently of the generator's hasher, so seeding
+/// with a `SeedLabel` gives different values to seeding with the string
+/// itself: `rng.seed_label(seed_label!("health"))` is not the same as
+/// `rng.seed("health")`. It's identical to seeding with the `SeedLabel`
+/// value, or with its [`hash`](SeedLabel::hash) as a `u64`. Switching an
+/// existing call between the two forms will therefore change its values.
+///
+/// The label hash is covered by the value-stability policy described on
+/// [`STREAM_VERSION`](crate::STREAM_VERSION).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+/// # {
+/// use pure_rng::{seed_label, PureRng, SeedLabel};
+///
+/// const HEALTH: SeedLabel = SeedLabel::new("health");
+///
+/// let rng = PureRng::new(1234);
+/// let health: u8 = rng.seed_label(HEALTH).gen();
+///
+/// assert_eq!(health, rng.seed_label(seed_label!("health")).gen());
+/// assert_eq!(health, rng.seed(HEALTH).gen());
+/// assert_eq!(health, rng.seed(HEALTH.hash()).gen());
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct SeedLabel {
+    name: &'static str,
+    hash: u64,
+}
+
+impl SeedLabel {
+    /// Hashes the given label. This is a `const fn`, so it can be used to
+    /// initialise a `const`.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            hash: hash_label(name.as_bytes()),
+        }
+    }
+
+    /// Returns the label string.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the hash of the label, as written to the generator's hasher.
+    pub const fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl Debug for SeedLabel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.name, f)
+    }
+}
+
+impl PartialEq for SeedLabel {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Eq for SeedLabel {}
+
+impl Hash for SeedLabel {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// Hashes the given bytes with 64-bit FNV-1a, followed by the SplitMix64
+/// finalizer to spread short labels across all the output bits.
+pub(crate) const fn hash_label(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
+/// Creates a [`SeedLabel`], hashing the string at compile time.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+/// # {
+/// use pure_rng::{seed_label, PureRng};
+///
+/// let rng = PureRng::new(1234);
+/// let heights: Vec<f32> = (0..100)
+///     .map(|x| rng.seed_label(seed_label!("height")).seed(x).gen())
+///     .collect();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! seed_label {
+    ($name:expr) => {{
+        const LABEL: $crate::SeedLabel = $crate::SeedLabel::new($name);
+        LABEL
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const() {
+        const LABEL: SeedLabel = seed_label!("monster generation");
+
+        assert_eq!(LABEL.name(), "monster generation");
+        assert_eq!(LABEL, SeedLabel::new("monster generation"));
+        assert_ne!(LABEL, SeedLabel::new("monster generation "));
+        assert_ne!(SeedLabel::new("").hash(), 0);
+        assert_eq!(format!("{LABEL:?}"), "\"monster generation\"");
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        assert_eq!(SeedLabel::new("").hash(), 0xf52a15e9a9b5e89b);
+        assert_eq!(SeedLabel::new("health").hash(), 0x739e63af2516812a);
+    }
+}
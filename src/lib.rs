@@ -1,18 +1,90 @@
-pub mod seq;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use rand::{
-    distributions::{
-        self,
-        uniform::{SampleRange, SampleUniform},
-        Distribution, Standard,
-    },
-    Fill, Rng, RngCore,
-};
+#[cfg(all(feature = "derive", not(any(feature = "rand_08", feature = "rand_09"))))]
+compile_error!("the `derive` feature requires either the `rand_08` or `rand_09` feature");
 
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+#[doc(hidden)]
+pub mod __private;
+#[cfg(feature = "alloc")]
+pub mod bag;
+#[cfg(feature = "dice")]
+pub mod dice;
+pub mod distributions;
+mod hash;
+pub mod hashers;
+mod label;
+#[cfg(feature = "loot")]
+pub mod loot;
+#[cfg(feature = "noise")]
+pub mod noise;
+#[cfg(feature = "rayon")]
+pub mod par;
+mod permutation;
+#[cfg(feature = "rand_08")]
+pub mod rand_08;
+#[cfg(feature = "rand_09")]
+pub mod rand_09;
+#[cfg(feature = "record")]
+pub mod record;
+#[cfg(feature = "seed_code")]
+pub mod seed_code;
+#[cfg(feature = "spatial")]
+pub mod spatial;
+mod state;
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+mod stream;
+#[cfg(feature = "trace")]
+pub mod trace;
+#[cfg(feature = "alloc")]
+mod weighted;
+mod wide;
+
+#[cfg(feature = "rand_08")]
+pub use rand_08::seq;
+#[cfg(all(feature = "rand_09", not(feature = "rand_08")))]
+pub use rand_09::seq;
+
+use core::hash::{Hash, Hasher};
+
+pub use distributions::PureGenerate;
+pub use hash::{Hasher128, StableHasher};
+pub use label::SeedLabel;
+pub use permutation::{PermutationIter, PurePermutation};
+#[cfg(feature = "derive")]
+pub use pure_rng_derive::PureGenerate;
+pub use state::{PureRngState, StateError};
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+pub use stream::PureStream;
+#[cfg(feature = "alloc")]
+pub use weighted::{PureWeightedTable, WeightedTableError};
+pub use wide::PureRandomGenerator128;
+
+use distributions::PureDistribution;
+
+/// The version of the value stream produced by this crate.
+///
+/// Given the same hasher, chain of seeds, and `rand` version, PureRng promises
+/// to produce the same values for as long as this number stays the same. This
+/// covers the raw values drawn from `RngCore::next_u64` and `fill_bytes`, and
+/// everything built on them here, eg. `gen_range`, `shuffle` and
+/// `seq::index::sample`. Functions implemented by `rand` are additionally
+/// subject to `rand`'s own value-stability policy, which holds within each
+/// minor version. The crate's test suite includes golden vectors for every
+/// supported hasher to enforce this.
+///
+/// If the stream ever has to change, this number will be incremented in a
+/// breaking release. Store it alongside persisted seeds to detect an
+/// incompatible upgrade at runtime.
+pub const STREAM_VERSION: u32 = 1;
+
+/// The default generator, using [`rapidhash::RapidHasher`] wrapped in a
+/// [`StableHasher`] so that seeds hash identically on every platform.
 #[cfg(feature = "rapidhash")]
-pub type PureRng = PureRandomGenerator<rapidhash::RapidHasher>;
+pub type PureRng = PureRandomGenerator<StableHasher<rapidhash::RapidHasher>>;
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -33,9 +105,12 @@ where
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(feature = "rapidhash")]
+    /// # {
     /// use pure_rng::PureRng;
     ///
     /// let rng = PureRng::new("initial seed");
+    /// # }
     /// ```
     pub fn new(hashable: impl Hash) -> Self {
         Self::default().seed(hashable)
@@ -51,6 +126,8 @@ where
     /// # Examples
     ///
     /// ```
+    /// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    /// # {
     /// use pure_rng::PureRng;
     ///
     /// let rng = PureRng::new("initial seed");
@@ -63,6 +140,7 @@ where
     /// let value_from_point: u64 = rng
     ///     .seed(Point { x: 10, y: 12 })
     ///     .gen();
+    /// # }
     /// ```
     pub fn seed(&self, hashable: impl Hash) -> Self {
         let mut fork = self.clone();
@@ -70,115 +148,207 @@ where
 
         fork
     }
-}
 
-impl<H> RngCore for PureRandomGenerator<H>
-where
-    H: Hasher + Default + Clone,
-{
-    fn next_u32(&mut self) -> u32 {
-        // Good enough for government work.
-        self.next_u64() as u32
+    /// Forks the generator, and advances the fork's state by writing the
+    /// pre-computed hash of the given label.
+    ///
+    /// This is a faster alternative to seeding with a string in hot loops, as
+    /// the string was hashed when creating the [`SeedLabel`]. Note that the
+    /// result differs from seeding with the string itself - see [`SeedLabel`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    /// # {
+    /// use pure_rng::{seed_label, PureRng};
+    ///
+    /// let rng = PureRng::new("initial seed");
+    /// let values: Vec<u64> = (0..10)
+    ///     .map(|i| rng.seed_label(seed_label!("tile")).seed(i).gen())
+    ///     .collect();
+    /// # }
+    /// ```
+    pub fn seed_label(&self, label: SeedLabel) -> Self {
+        let mut fork = self.clone();
+        fork.hasher.write_u64(label.hash());
+
+        fork
     }
 
-    fn next_u64(&mut self) -> u64 {
-        // Get the random value by finishing the hasher.
-        let val = self.hasher.finish();
+    /// Fills `dest` with the values drawn from the generator seeded with
+    /// consecutive integers, starting from `start`.
+    ///
+    /// This produces exactly the same values as the common pattern of seeding
+    /// with each index and drawing a `u64`, ie. `dest[i]` is
+    /// `rng.seed(start + i as u64).gen::<u64>()`, but hashes the indices in
+    /// batches for speed. The index wraps around on overflow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    /// # {
+    /// use pure_rng::PureRng;
+    ///
+    /// let rng = PureRng::new("initial seed");
+    /// let mut values = [0u64; 100];
+    /// rng.fill_indexed(1000, &mut values);
+    ///
+    /// assert_eq!(values[5], rng.seed(1005u64).gen::<u64>());
+    /// # }
+    /// ```
+    pub fn fill_indexed(&self, start: u64, dest: &mut [u64]) {
+        // Hashing several independent indices at once lets the CPU overlap
+        // their work, and the compiler vectorise it where the hasher allows.
+        const LANES: usize = 8;
 
-        // Write the value back into the hasher to advance the state. Continuing
-        // to use the hasher after you've finished it is explicitly supported.
-        self.hasher.write_u64(val);
+        let mut chunks = dest.chunks_exact_mut(LANES);
+        let mut index = start;
 
-        val
-    }
+        for chunk in &mut chunks {
+            let mut hashers: [H; LANES] = core::array::from_fn(|_| self.hasher.clone());
+            for (lane, hasher) in hashers.iter_mut().enumerate() {
+                hasher.write_u64(index.wrapping_add(lane as u64));
+            }
+            for (value, hasher) in chunk.iter_mut().zip(&hashers) {
+                *value = hasher.finish();
+            }
 
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        rand_core::impls::fill_bytes_via_next(self, dest);
-    }
+            index = index.wrapping_add(LANES as u64);
+        }
 
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        self.fill_bytes(dest);
-        Ok(())
+        for value in chunks.into_remainder() {
+            let mut hasher = self.hasher.clone();
+            hasher.write_u64(index);
+            *value = hasher.finish();
+
+            index = index.wrapping_add(1);
+        }
     }
-}
 
-/// Wrappers for the [`Rng`] trait functions.
-impl<H> PureRandomGenerator<H>
-where
-    H: Hasher + Default + Clone,
-{
-    /// Return a random value supporting the [`Standard`] distribution.
+    /// Fills `dest` with floats in `[0, 1)` from the generator seeded with
+    /// consecutive integers, starting from `start`.
     ///
-    /// See [`Rng::gen`].
-    #[inline]
-    pub fn gen<T>(mut self) -> T
-    where
-        Standard: Distribution<T>,
-    {
-        Rng::gen(&mut self)
+    /// `dest[i]` is `rng.seed(start + i as u64).gen::<f64>()`. See
+    /// [`fill_indexed`](Self::fill_indexed).
+    pub fn fill_indexed_f64(&self, start: u64, dest: &mut [f64]) {
+        // The same conversion as `rand`'s `Standard` distribution, which uses
+        // the top 53 bits of a `u64`. They fit in an `i64`, which converts to a
+        // float faster than a `u64` does.
+        self.fill_indexed_with(start, dest, |value| {
+            (value >> 11) as i64 as f64 * (1. / (1u64 << 53) as f64)
+        });
     }
 
-    /// Generate a random value in the given range.
+    /// Fills `dest` with floats in `[0, 1)` from the generator seeded with
+    /// consecutive integers, starting from `start`.
     ///
-    /// See [`Rng::gen_range`].
-    pub fn gen_range<T, Q>(mut self, range: Q) -> T
-    where
-        T: SampleUniform,
-        Q: SampleRange<T>,
-    {
-        Rng::gen_range(&mut self, range)
+    /// `dest[i]` is `rng.seed(start + i as u64).gen::<f32>()`. See
+    /// [`fill_indexed`](Self::fill_indexed).
+    pub fn fill_indexed_f32(&self, start: u64, dest: &mut [f32]) {
+        // The same conversion as `rand`'s `Standard` distribution, which uses
+        // the top 24 bits of a `u32`, and our `next_u32` truncates the `u64`.
+        self.fill_indexed_with(start, dest, |value| {
+            (value as u32 >> 8) as i32 as f32 * (1. / (1u32 << 24) as f32)
+        });
     }
 
-    /// Sample a new value, using the given distribution.
-    ///
-    /// See [`Rng::sample`].
-    pub fn sample<T, D: Distribution<T>>(mut self, distr: D) -> T {
-        Rng::sample(&mut self, distr)
+    fn fill_indexed_with<T>(&self, start: u64, dest: &mut [T], convert: impl Fn(u64) -> T) {
+        // Hash into a buffer first, so that the hashing loop stays free of
+        // conversions and can be optimised as well as `fill_indexed`. The
+        // buffer is zeroed on every call, so it's kept small enough for that
+        // to cost little next to hashing even a short slice.
+        let mut buffer = [0; 32];
+        let mut index = start;
+
+        for chunk in dest.chunks_mut(buffer.len()) {
+            let hashes = &mut buffer[..chunk.len()];
+            self.fill_indexed(index, hashes);
+
+            for (value, &hash) in chunk.iter_mut().zip(&*hashes) {
+                *value = convert(hash);
+            }
+
+            index = index.wrapping_add(chunk.len() as u64);
+        }
     }
 
-    /// Create an iterator that generates values using the given distribution.
-    ///
-    /// See [`Rng::sample_iter`].
-    pub fn sample_iter<T, D>(self, distr: D) -> distributions::DistIter<D, Self, T>
-    where
-        D: Distribution<T>,
-        Self: Sized,
-    {
-        Rng::sample_iter(self, distr)
+    /// Sample a new value, using the given [`PureDistribution`]. Unlike
+    /// `sample`, the distribution receives the generator itself and so is able
+    /// to seed it further.
+    pub fn sample_pure<T, D: PureDistribution<T>>(self, distr: D) -> T {
+        distr.sample(self)
     }
 
-    /// Fill any type implementing [`Fill`] with random data.
-    ///
-    /// See [`Rng::fill`].
-    pub fn fill<T: Fill + ?Sized>(mut self, dest: &mut T) {
-        Rng::fill(&mut self, dest)
+    /// Draws the next raw value. This is the source of randomness behind every
+    /// `RngCore` implementation.
+    #[cfg_attr(not(any(feature = "rand_08", feature = "rand_09")), allow(dead_code))]
+    pub(crate) fn draw(&mut self) -> u64 {
+        // Get the random value by finishing the hasher.
+        let val = self.hasher.finish();
+
+        // Write the value back into the hasher to advance the state. Continuing
+        // to use the hasher after you've finished it is explicitly supported.
+        self.hasher.write_u64(val);
+
+        val
     }
 
-    /// Fill any type implementing [`Fill`] with random data
-    ///
-    /// See [`Rng::try_fill`].
-    pub fn try_fill<T: Fill + ?Sized>(mut self, dest: &mut T) -> Result<(), rand::Error> {
-        Rng::try_fill(&mut self, dest)
+    /// Returns the generator for the given integer lattice point. This is
+    /// equivalent to `self.seed((x, y, ...))`, without creating an
+    /// intermediate generator per coordinate.
+    #[cfg_attr(not(any(feature = "noise", feature = "spatial")), allow(dead_code))]
+    pub(crate) fn seed_lattice<const N: usize>(&self, point: [i64; N]) -> Self {
+        let mut fork = self.clone();
+        for coordinate in point {
+            coordinate.hash(&mut fork.hasher);
+        }
+
+        fork
     }
 
-    /// Return a bool with a probability `p` of being true.
-    ///
-    /// See [`Rng::gen_bool`].
-    #[inline]
-    pub fn gen_bool(mut self, p: f64) -> bool {
-        Rng::gen_bool(&mut self, p)
+    /// Draws a uniformly distributed integer below `range`, using Lemire's
+    /// multiply-and-reject method. Unlike the `rand` range sampling, this is
+    /// independent of the `rand` version.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    pub(crate) fn draw_below(&mut self, range: u64) -> u64 {
+        let threshold = range.wrapping_neg() % range;
+
+        loop {
+            let product = self.draw() as u128 * range as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
     }
 
-    /// Return a bool with a probability of `numerator/denominator` of being true.
-    ///
-    /// See [`Rng::gen_ratio`].
-    #[inline]
-    pub fn gen_ratio(mut self, numerator: u32, denominator: u32) -> bool {
-        Rng::gen_ratio(&mut self, numerator, denominator)
+    /// Draws a uniformly distributed `f64` in `[0, 1)`, from the top 53 bits
+    /// of a draw. Unlike the `rand` `Standard` distribution, this is
+    /// independent of the `rand` version.
+    #[cfg_attr(
+        not(any(feature = "alloc", feature = "noise", feature = "spatial")),
+        allow(dead_code)
+    )]
+    pub(crate) fn draw_unit(&mut self) -> f64 {
+        (self.draw() >> 11) as f64 * (1. / (1u64 << 53) as f64)
+    }
+}
+
+/// Rounds down to an integer, saturating at the limits of `i64`. Unlike
+/// `f64::floor`, this is available without `std`.
+#[cfg(any(feature = "noise", feature = "spatial"))]
+pub(crate) fn floor(x: f64) -> i64 {
+    let i = x as i64;
+
+    if (i as f64) > x {
+        i.saturating_sub(1)
+    } else {
+        i
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rapidhash", feature = "rand_08"))]
 mod tests {
     use super::*;
 
@@ -207,4 +377,44 @@ mod tests {
         assert_ne!(val_3, val_5);
         assert_ne!(val_2, val_5);
     }
+
+    #[test]
+    fn test_fill_indexed() {
+        let rng = PureRng::new(1234);
+
+        for start in [0, 1000, u64::MAX - 5] {
+            let mut values = [0u64; 21];
+            rng.fill_indexed(start, &mut values);
+            let mut floats = [0f64; 21];
+            rng.fill_indexed_f64(start, &mut floats);
+            let mut small_floats = [0f32; 21];
+            rng.fill_indexed_f32(start, &mut small_floats);
+
+            for i in 0..21 {
+                let rng = rng.seed(start.wrapping_add(i as u64));
+
+                assert_eq!(values[i], rng.gen::<u64>());
+                assert_eq!(floats[i], rng.gen::<f64>());
+                assert_eq!(small_floats[i], rng.gen::<f32>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_seedable_rng() {
+        use rand::{RngCore, SeedableRng};
+
+        fn generic<R: SeedableRng>(state: u64) -> R {
+            R::seed_from_u64(state)
+        }
+
+        assert!(generic::<PureRng>(1234) == PureRng::new(1234u64));
+        assert!(PureRng::seed_from_u64(1234) == PureRng::new(1234u64));
+        assert!(PureRng::from_seed(1234u64.to_le_bytes()) == PureRng::new(1234u64));
+
+        let mut source = PureRng::new("source");
+        let expected = PureRng::new(source.next_u64());
+        let from_rng = PureRng::from_rng(PureRng::new("source")).unwrap();
+        assert!(from_rng == expected);
+    }
 }
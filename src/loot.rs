@@ -0,0 +1,648 @@
+//! Loot tables, enabled by the `loot` feature.
+//!
+//! A [`LootTable`] is a declarative description of what can drop: weighted
+//! entries that are picked one or more times, entries that always drop,
+//! quantity ranges, entries that only apply under certain conditions, and
+//! entries that roll a nested table. With the `serde` feature tables can be
+//! loaded from any serde format, such as RON, JSON or TOML:
+//!
+//! ```toml
+//! rolls = { min = 1, max = 3 }
+//!
+//! [[entries]]
+//! id = "gold"
+//! guaranteed = true
+//! quantity = { min = 5, max = 20 }
+//! item = "gold coin"
+//!
+//! [[entries]]
+//! id = "sword"
+//! weight = 2.0
+//! item = "rusty sword"
+//!
+//! [[entries]]
+//! id = "gems"
+//! conditions = ["boss"]
+//! table = { entries = [{ id = "ruby", item = "ruby" }, { id = "opal", item = "opal" }] }
+//!
+//! [[entries]]
+//! id = "nothing"
+//! weight = 5.0
+//! ```
+//!
+//! In RON, the optional `item` and `table` fields are written as `Some(..)`
+//! unless the `implicit_some` extension is enabled.
+//!
+//! # Stability
+//!
+//! Each entry draws from its own generator, seeded by its identifier: the
+//! `r`th pick of entry `id` in a table rolled with `rng` uses
+//! `rng.seed(id).seed(r)`, with `r` as a `u32`. Weighted picks are made by
+//! giving every entry a random key scaled by its weight, and picking the
+//! highest, so adding, removing or reweighting an entry only changes the picks
+//! that entry now wins or loses. Every other drop stays the same. Quantities
+//! and nested tables are drawn from separate generators again, so changing an
+//! entry's quantity doesn't change what it rolls on a nested table.
+//!
+//! The drops are independent of the `rand` version, and are covered by the
+//! value-stability policy described on [`STREAM_VERSION`](crate::STREAM_VERSION).
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{
+//!     loot::{Loot, LootEntry, LootTable, Quantity},
+//!     PureRng,
+//! };
+//!
+//! let table = LootTable {
+//!     rolls: Quantity::new(2, 2),
+//!     unique: true,
+//!     entries: vec![
+//!         LootEntry::new("gold", Loot::Item("gold coin")).guaranteed().quantity(5, 20),
+//!         LootEntry::new("sword", Loot::Item("sword")).weight(2.0),
+//!         LootEntry::new("shield", Loot::Item("shield")),
+//!         LootEntry::new("crown", Loot::Item("crown")).condition("boss"),
+//!     ],
+//! };
+//!
+//! let rng = PureRng::new("dungeon").seed("chest 1");
+//! let drops = table.roll(rng);
+//! assert_eq!(*drops[0].item, "gold coin");
+//! assert_eq!(drops.len(), 3);
+//!
+//! let boss_drops = table.roll_with(rng, |condition| condition == "boss");
+//! assert_eq!(boss_drops[0], drops[0]);
+//! # }
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::hash::Hasher;
+
+use crate::PureRandomGenerator;
+
+/// A table of weighted loot entries.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LootTable<T> {
+    /// How many times to pick from the weighted entries. Defaults to once.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rolls: Quantity,
+    /// Whether each entry can be picked at most once per roll of the table.
+    /// Defaults to `false`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unique: bool,
+    /// The entries of the table.
+    pub entries: Vec<LootEntry<T>>,
+}
+
+/// An entry in a [`LootTable`].
+///
+/// With the `serde` feature, the loot is written as either an `item` or a
+/// `table` field, or neither for an entry that drops nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootEntry<T> {
+    /// The identifier the entry's drops are seeded by. Should be unique within
+    /// its table.
+    pub id: String,
+    /// The relative chance of picking the entry. Entries with a weight of zero
+    /// or less are never picked. Defaults to 1.
+    pub weight: f64,
+    /// Whether the entry always drops, rather than being picked. Defaults to
+    /// `false`.
+    pub guaranteed: bool,
+    /// How many of the item drop each time the entry does. Defaults to one.
+    pub quantity: Quantity,
+    /// Conditions which must all hold for the entry to be considered.
+    pub conditions: Vec<String>,
+    /// What the entry drops. `None` makes an entry that drops nothing, which
+    /// is useful for weighting the chance of an empty roll.
+    pub loot: Option<Loot<T>>,
+}
+
+/// What a [`LootEntry`] drops.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Loot<T> {
+    /// A single item.
+    Item(T),
+    /// A roll of a nested table, once for every one of the quantity.
+    Table(Box<LootTable<T>>),
+}
+
+/// An inclusive range of counts, such as the number of items that drop.
+///
+/// With the `serde` feature, a fixed count is written as a plain integer and a
+/// range as `{ min, max }`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "QuantityRepr", into = "QuantityRepr")
+)]
+pub struct Quantity {
+    /// The lowest count.
+    pub min: u32,
+    /// The highest count. Treated as `min` if it's lower.
+    pub max: u32,
+}
+
+/// An item dropped by rolling a [`LootTable`].
+#[derive(Debug, PartialEq)]
+pub struct LootDrop<'a, T> {
+    /// The identifier of the entry the item came from.
+    pub id: &'a str,
+    /// The item.
+    pub item: &'a T,
+    /// How many of the item dropped.
+    pub quantity: u32,
+}
+
+impl<T> Clone for LootDrop<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for LootDrop<'_, T> {}
+
+impl<T> LootTable<T> {
+    /// Creates a table which picks once from the given entries.
+    pub fn new(entries: Vec<LootEntry<T>>) -> Self {
+        Self {
+            rolls: Quantity::default(),
+            unique: false,
+            entries,
+        }
+    }
+
+    /// Rolls the table, skipping entries with conditions.
+    ///
+    /// Guaranteed entries drop first, in the order of the table, followed by
+    /// the picks. Nested tables are rolled in place.
+    pub fn roll<H>(&self, rng: PureRandomGenerator<H>) -> Vec<LootDrop<'_, T>>
+    where
+        H: Hasher + Default + Clone,
+    {
+        self.roll_with(rng, |_| false)
+    }
+
+    /// Rolls the table, including entries whose conditions all hold according
+    /// to `condition`.
+    ///
+    /// Changing the outcome of a condition can only affect the picks its
+    /// entries win or lose.
+    pub fn roll_with<H, F>(&self, rng: PureRandomGenerator<H>, condition: F) -> Vec<LootDrop<'_, T>>
+    where
+        H: Hasher + Default + Clone,
+        F: Fn(&str) -> bool,
+    {
+        let mut drops = Vec::new();
+        self.roll_into(&rng, &condition, &mut drops);
+
+        drops
+    }
+
+    fn roll_into<'a, H>(
+        &'a self,
+        rng: &PureRandomGenerator<H>,
+        condition: &dyn Fn(&str) -> bool,
+        drops: &mut Vec<LootDrop<'a, T>>,
+    ) where
+        H: Hasher + Default + Clone,
+    {
+        let available = |entry: &&LootEntry<T>| entry.conditions.iter().all(|name| condition(name));
+
+        for entry in self
+            .entries
+            .iter()
+            .filter(|e| e.guaranteed)
+            .filter(available)
+        {
+            entry.drop_into(rng, 0, condition, drops);
+        }
+
+        let candidates: Vec<&LootEntry<T>> = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.guaranteed && entry.weight > 0.0)
+            .filter(available)
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+
+        let rolls = self.rolls.sample(&mut rng.seed("rolls"));
+
+        if self.unique {
+            // The first keys of each entry rank them all at once, which is
+            // equivalent to picking without replacement.
+            let mut ranked: Vec<(f64, &LootEntry<T>)> = candidates
+                .iter()
+                .map(|entry| (entry.key(rng, 0), *entry))
+                .collect();
+            ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+            for (_, entry) in ranked.into_iter().take(rolls as usize) {
+                entry.drop_into(rng, 0, condition, drops);
+            }
+        } else {
+            for roll in 0..rolls {
+                let (_, entry) = candidates
+                    .iter()
+                    .map(|entry| (entry.key(rng, roll), *entry))
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .unwrap();
+
+                entry.drop_into(rng, roll, condition, drops);
+            }
+        }
+    }
+}
+
+impl<T> LootEntry<T> {
+    /// Creates an entry with a weight of 1 which drops one of `loot`.
+    pub fn new(id: impl Into<String>, loot: Loot<T>) -> Self {
+        Self::with_loot(id.into(), Some(loot))
+    }
+
+    /// Creates an entry with a weight of 1 which drops nothing.
+    pub fn nothing(id: impl Into<String>) -> Self {
+        Self::with_loot(id.into(), None)
+    }
+
+    fn with_loot(id: String, loot: Option<Loot<T>>) -> Self {
+        Self {
+            id,
+            weight: 1.0,
+            guaranteed: false,
+            quantity: Quantity::default(),
+            conditions: Vec::new(),
+            loot,
+        }
+    }
+
+    /// Sets the weight of the entry.
+    pub fn weight(self, weight: f64) -> Self {
+        Self { weight, ..self }
+    }
+
+    /// Makes the entry always drop.
+    pub fn guaranteed(self) -> Self {
+        Self {
+            guaranteed: true,
+            ..self
+        }
+    }
+
+    /// Sets the range of how many of the item drop.
+    pub fn quantity(self, min: u32, max: u32) -> Self {
+        Self {
+            quantity: Quantity::new(min, max),
+            ..self
+        }
+    }
+
+    /// Adds a condition which must hold for the entry to be considered.
+    pub fn condition(mut self, condition: impl Into<String>) -> Self {
+        self.conditions.push(condition.into());
+        self
+    }
+
+    /// The entry's key for the given pick. The highest key wins.
+    fn key<H>(&self, rng: &PureRandomGenerator<H>, roll: u32) -> f64
+    where
+        H: Hasher + Default + Clone,
+    {
+        // Efraimidis and Spirakis' weighted sampling: with `u` uniform in
+        // (0, 1], the entry with the highest `u^(1 / weight)` is picked with
+        // probability proportional to its weight. Comparing logarithms gives
+        // the same order.
+        let mut rng = rng.seed(self.id.as_str()).seed(roll);
+        let u = ((rng.draw() >> 11) + 1) as f64 / (1u64 << 53) as f64;
+
+        u.ln() / self.weight
+    }
+
+    /// Adds the entry's drops for the given pick.
+    fn drop_into<'a, H>(
+        &'a self,
+        rng: &PureRandomGenerator<H>,
+        roll: u32,
+        condition: &dyn Fn(&str) -> bool,
+        drops: &mut Vec<LootDrop<'a, T>>,
+    ) where
+        H: Hasher + Default + Clone,
+    {
+        let rng = rng.seed(self.id.as_str()).seed(roll);
+        let quantity = self.quantity.sample(&mut rng.seed("quantity"));
+
+        match &self.loot {
+            None => {}
+            Some(Loot::Item(item)) if quantity > 0 => drops.push(LootDrop {
+                id: &self.id,
+                item,
+                quantity,
+            }),
+            Some(Loot::Item(_)) => {}
+            Some(Loot::Table(table)) => {
+                let rng = rng.seed("table");
+                for i in 0..quantity {
+                    table.roll_into(&rng.seed(i), condition, drops);
+                }
+            }
+        }
+    }
+}
+
+impl Quantity {
+    /// Creates a range from `min` to `max` inclusive.
+    pub const fn new(min: u32, max: u32) -> Self {
+        Self { min, max }
+    }
+
+    /// Draws a count from the range.
+    fn sample<H>(&self, rng: &mut PureRandomGenerator<H>) -> u32
+    where
+        H: Hasher + Default + Clone,
+    {
+        if self.max <= self.min {
+            return self.min;
+        }
+
+        self.min + rng.draw_below(u64::from(self.max - self.min) + 1) as u32
+    }
+}
+
+impl Default for Quantity {
+    /// Exactly one.
+    fn default() -> Self {
+        Self::new(1, 1)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// The serialized form of a [`LootEntry`], with the loot split into optional
+/// `item` and `table` fields.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct EntryRepr<T> {
+    id: String,
+    #[serde(default = "default_weight")]
+    weight: f64,
+    #[serde(default)]
+    guaranteed: bool,
+    #[serde(default)]
+    quantity: Quantity,
+    #[serde(default)]
+    conditions: Vec<String>,
+    item: Option<T>,
+    table: Option<Box<LootTable<T>>>,
+}
+
+/// A borrowed [`EntryRepr`], for serializing without cloning.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct EntryReprRef<'a, T> {
+    id: &'a str,
+    weight: f64,
+    guaranteed: bool,
+    quantity: Quantity,
+    conditions: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<&'a T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    table: Option<&'a LootTable<T>>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for LootEntry<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (item, table) = match &self.loot {
+            Some(Loot::Item(item)) => (Some(item), None),
+            Some(Loot::Table(table)) => (None, Some(&**table)),
+            None => (None, None),
+        };
+
+        EntryReprRef {
+            id: &self.id,
+            weight: self.weight,
+            guaranteed: self.guaranteed,
+            quantity: self.quantity,
+            conditions: &self.conditions,
+            item,
+            table,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for LootEntry<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = EntryRepr::deserialize(deserializer)?;
+        let loot = match (repr.item, repr.table) {
+            (Some(item), None) => Some(Loot::Item(item)),
+            (None, Some(table)) => Some(Loot::Table(table)),
+            (None, None) => None,
+            (Some(_), Some(_)) => {
+                return Err(serde::de::Error::custom(
+                    "loot entry has both an item and a table",
+                ))
+            }
+        };
+
+        Ok(Self {
+            id: repr.id,
+            weight: repr.weight,
+            guaranteed: repr.guaranteed,
+            quantity: repr.quantity,
+            conditions: repr.conditions,
+            loot,
+        })
+    }
+}
+
+/// The serialized form of a [`Quantity`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum QuantityRepr {
+    Fixed(u32),
+    Range { min: u32, max: u32 },
+}
+
+#[cfg(feature = "serde")]
+impl From<QuantityRepr> for Quantity {
+    fn from(repr: QuantityRepr) -> Self {
+        match repr {
+            QuantityRepr::Fixed(count) => Self::new(count, count),
+            QuantityRepr::Range { min, max } => Self::new(min, max),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Quantity> for QuantityRepr {
+    fn from(quantity: Quantity) -> Self {
+        if quantity.min == quantity.max {
+            Self::Fixed(quantity.min)
+        } else {
+            Self::Range {
+                min: quantity.min,
+                max: quantity.max,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    fn items<'a>(drops: &[LootDrop<'a, &'a str>]) -> Vec<&'a str> {
+        drops.iter().map(|drop| *drop.item).collect()
+    }
+
+    fn weighted() -> LootTable<&'static str> {
+        LootTable {
+            rolls: Quantity::new(5, 5),
+            unique: false,
+            entries: vec![
+                LootEntry::new("a", Loot::Item("a")),
+                LootEntry::new("b", Loot::Item("b")).weight(3.0),
+                LootEntry::new("c", Loot::Item("c")).weight(0.5),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_weights() {
+        let table = LootTable::new(vec![
+            LootEntry::new("common", Loot::Item("common")).weight(3.0),
+            LootEntry::new("rare", Loot::Item("rare")),
+            LootEntry::new("never", Loot::Item("never")).weight(0.0),
+        ]);
+
+        let rng = PureRng::new("weights");
+        let rare = (0..10_000u32)
+            .map(|i| table.roll(rng.seed(i)))
+            .inspect(|drops| assert_eq!(drops.len(), 1))
+            .filter(|drops| *drops[0].item == "rare")
+            .count();
+
+        assert!((2300..2700).contains(&rare), "{rare}");
+    }
+
+    #[test]
+    fn test_unrelated_entries() {
+        let table = weighted();
+        let mut extended = weighted();
+        extended
+            .entries
+            .insert(1, LootEntry::new("d", Loot::Item("d")).weight(0.01));
+
+        let rng = PureRng::new("stable");
+        let mut changed = 0;
+        for i in 0..200u32 {
+            let before = items(&table.roll(rng.seed(i)));
+            let after = items(&extended.roll(rng.seed(i)));
+
+            for (before, after) in before.iter().zip(&after) {
+                if after == &"d" {
+                    changed += 1;
+                } else {
+                    assert_eq!(before, after);
+                }
+            }
+        }
+
+        assert!(changed < 10);
+    }
+
+    #[test]
+    fn test_unique() {
+        let mut table = weighted();
+        table.unique = true;
+
+        for i in 0..100u32 {
+            let mut drops = items(&table.roll(PureRng::new(i)));
+            drops.sort();
+            assert_eq!(drops, ["a", "b", "c"]);
+        }
+
+        table.rolls = Quantity::new(2, 2);
+        let drops = items(&table.roll(PureRng::new(0u32)));
+        assert_eq!(drops.len(), 2);
+        assert_ne!(drops[0], drops[1]);
+    }
+
+    #[test]
+    fn test_guaranteed_and_quantity() {
+        let table = LootTable::new(vec![
+            LootEntry::new("gold", Loot::Item("gold"))
+                .guaranteed()
+                .quantity(5, 20),
+            LootEntry::nothing("nothing"),
+        ]);
+
+        for i in 0..100u32 {
+            let drops = table.roll(PureRng::new(i));
+            assert_eq!(drops.len(), 1);
+            assert!((5..=20).contains(&drops[0].quantity));
+        }
+    }
+
+    #[test]
+    fn test_conditions() {
+        let table = LootTable {
+            rolls: Quantity::new(3, 3),
+            unique: true,
+            entries: vec![
+                LootEntry::new("night", Loot::Item("night")).condition("night"),
+                LootEntry::new("both", Loot::Item("both"))
+                    .condition("night")
+                    .condition("boss"),
+                LootEntry::new("always", Loot::Item("always")),
+            ],
+        };
+
+        let rng = PureRng::new("conditions");
+        assert_eq!(items(&table.roll(rng)), ["always"]);
+
+        let mut night = items(&table.roll_with(rng, |c| c == "night"));
+        night.sort();
+        assert_eq!(night, ["always", "night"]);
+
+        assert_eq!(table.roll_with(rng, |_| true).len(), 3);
+    }
+
+    #[test]
+    fn test_nested() {
+        let gems = LootTable::new(vec![
+            LootEntry::new("ruby", Loot::Item("ruby")),
+            LootEntry::new("opal", Loot::Item("opal")),
+        ]);
+        let table = LootTable::new(vec![LootEntry::new(
+            "gems",
+            Loot::Table(Box::new(gems.clone())),
+        )
+        .quantity(3, 3)]);
+
+        let rng = PureRng::new("nested");
+        let drops = table.roll(rng);
+        assert_eq!(drops.len(), 3);
+
+        // Each of the quantity rolls the nested table with its own seed.
+        let table_rng = rng.seed("gems").seed(0u32).seed("table");
+        for (i, drop) in drops.iter().enumerate() {
+            assert_eq!(*drop, gems.roll(table_rng.seed(i as u32))[0]);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use core::hash::Hash;
+
+use super::{abs, Noise, SeedNoise};
+
+/// Defines a fractal combinator, which sums octaves of its source noise with
+/// increasing frequency and decreasing amplitude. Each octave after the first
+/// uses the source seeded with the octave number.
+macro_rules! fractal {
+    ($(#[$attr:meta])* $name:ident, |$value:ident| $octave:expr, |$total:ident| $finish:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        pub struct $name<T> {
+            /// The noise to sum octaves of.
+            pub source: T,
+            /// The number of octaves. Defaults to 6.
+            pub octaves: u32,
+            /// The frequency of the first octave. Defaults to 1.
+            pub frequency: f64,
+            /// The frequency multiplier between octaves. Defaults to 2.
+            pub lacunarity: f64,
+            /// The amplitude multiplier between octaves. Defaults to 0.5.
+            pub persistence: f64,
+        }
+
+        impl<T> $name<T> {
+            /// Sums octaves of the given noise, with the default settings.
+            pub fn new(source: T) -> Self {
+                Self {
+                    source,
+                    octaves: 6,
+                    frequency: 1.,
+                    lacunarity: 2.,
+                    persistence: 0.5,
+                }
+            }
+        }
+
+        impl<T, const N: usize> Noise<N> for $name<T>
+        where
+            T: Noise<N> + SeedNoise,
+        {
+            fn get(&self, point: [f64; N]) -> f64 {
+                let mut frequency = self.frequency;
+                let mut amplitude = 1.;
+                let mut $total = 0.;
+                let mut amplitudes = 0.;
+
+                for octave in 0..self.octaves {
+                    let point = point.map(|x| x * frequency);
+                    let $value = if octave == 0 {
+                        self.source.get(point)
+                    } else {
+                        self.source.seed(octave).get(point)
+                    };
+
+                    $total += $octave * amplitude;
+                    amplitudes += amplitude;
+
+                    frequency *= self.lacunarity;
+                    amplitude *= self.persistence;
+                }
+
+                if amplitudes > 0. {
+                    $total /= amplitudes;
+                }
+
+                $finish
+            }
+        }
+
+        impl<T: SeedNoise> SeedNoise for $name<T> {
+            fn seed(&self, hashable: impl Hash) -> Self {
+                Self {
+                    source: self.source.seed(hashable),
+                    ..*self
+                }
+            }
+        }
+    };
+}
+
+fractal!(
+    /// Fractal Brownian motion: the plain sum of octaves of the source noise.
+    ///
+    /// Each octave adds finer detail, producing the typical rolling-hills
+    /// heightmap.
+    Fbm,
+    |value| value,
+    |total| total
+);
+
+fractal!(
+    /// Ridged noise: octaves of the source noise folded around zero and
+    /// inverted, giving sharp ridges where the source crosses zero.
+    ///
+    /// Useful for mountain ranges and rivers.
+    Ridged,
+    |value| {
+        let ridge = 1. - abs(value);
+        ridge * ridge
+    },
+    |total| total * 2. - 1.
+);
+
+fractal!(
+    /// Billow noise: octaves of the absolute value of the source noise, giving
+    /// rounded lumps with creases in between.
+    ///
+    /// Useful for clouds and rocks.
+    Billow,
+    |value| abs(value),
+    |total| total * 2. - 1.
+);
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{
+        noise::{tests::check_coherent, Perlin},
+        PureRng,
+    };
+
+    #[test]
+    fn test_coherent() {
+        let source = Perlin::new(PureRng::new(1234));
+
+        check_coherent::<2>(&Fbm::new(source));
+        check_coherent::<3>(&Ridged::new(source));
+        check_coherent::<2>(&Billow::new(source));
+    }
+
+    #[test]
+    fn test_octaves() {
+        let source = Perlin::new(PureRng::new(1234));
+        let point = [1.3, 2.7];
+
+        let fbm = Fbm {
+            octaves: 1,
+            ..Fbm::new(source)
+        };
+        assert_eq!(fbm.get(point), source.get(point));
+
+        let fbm = Fbm {
+            octaves: 2,
+            persistence: 1.,
+            ..Fbm::new(source)
+        };
+        let expected = (source.get(point) + source.seed(1).get(point.map(|x| x * 2.))) / 2.;
+        assert_eq!(fbm.get(point), expected);
+    }
+}
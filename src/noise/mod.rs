@@ -0,0 +1,309 @@
+//! Coordinate-keyed coherent noise, enabled by the `noise` feature.
+//!
+//! Each noise function wraps a [`PureRandomGenerator`], and takes the random
+//! value at every lattice point from the generator seeded with the point's
+//! integer coordinates, ie. `rng.seed((x, y))`. The same generator therefore
+//! always produces the same terrain, and different parts of a world can use
+//! independent noise simply by seeding the generator differently.
+//!
+//! The basis functions are [`ValueNoise`], [`Perlin`], [`OpenSimplex2`] and
+//! [`Worley`], all implementing [`Noise`] in 1 to 4 dimensions and returning
+//! values in `[-1, 1]`. The [`Fbm`], [`Ridged`] and [`Billow`] combinators sum several
+//! octaves of a basis function, seeding it with the octave number to keep the
+//! octaves independent.
+//!
+//! Only basic floating point arithmetic is used, so the values are identical
+//! on every platform.
+//!
+//! Coordinates are clamped to `±2^52`, beyond which an `f64` can't hold any
+//! fraction of a lattice cell, so the noise is constant past that distance
+//! along each axis.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{
+//!     noise::{Fbm, Noise, OpenSimplex2},
+//!     PureRng,
+//! };
+//!
+//! let world = PureRng::new("world seed");
+//! let terrain = Fbm {
+//!     octaves: 5,
+//!     frequency: 1. / 64.,
+//!     ..Fbm::new(OpenSimplex2::new(world.seed("terrain")))
+//! };
+//!
+//! let height = terrain.get([12., 34.]);
+//! assert!((-1.0..=1.0).contains(&height));
+//! assert_eq!(height, terrain.get([12., 34.]));
+//! # }
+//! ```
+
+mod fractal;
+mod perlin;
+mod simplex;
+mod value;
+mod worley;
+
+use core::hash::{Hash, Hasher};
+
+pub use fractal::{Billow, Fbm, Ridged};
+pub use perlin::Perlin;
+pub use simplex::OpenSimplex2;
+pub use value::ValueNoise;
+pub use worley::{Distance, Site, Worley, WorleyOutput};
+
+use crate::{floor, PureRandomGenerator};
+
+/// An `N`-dimensional noise function.
+pub trait Noise<const N: usize> {
+    /// Returns the value of the noise at the given point.
+    fn get(&self, point: [f64; N]) -> f64;
+}
+
+/// Noise functions which can be forked into independent copies, in the same
+/// way as [`PureRandomGenerator::seed`].
+pub trait SeedNoise {
+    /// Returns a copy of the noise function, with its generator seeded with
+    /// the given value.
+    fn seed(&self, hashable: impl Hash) -> Self;
+}
+
+/// Returns a value in `[-1, 1)` from the given lattice point.
+fn lattice_value<H, const N: usize>(rng: &PureRandomGenerator<H>, point: [i64; N]) -> f64
+where
+    H: Hasher + Default + Clone,
+{
+    to_signed_unit(rng.seed_lattice(point).draw())
+}
+
+/// Returns a gradient vector from the given lattice point's hash.
+///
+/// In 1D this is a value in `[-1, 1)`. In 2D it's one of the 8 unit vectors at
+/// multiples of 45°. In higher dimensions it's one of the vectors with a single
+/// zero component and all others ±1, ie. the midpoints of the edges of a cube
+/// in 3D, as in Ken Perlin's improved noise.
+fn gradient<const N: usize>(hash: u64) -> [f64; N] {
+    const DIAGONAL: f64 = core::f64::consts::FRAC_1_SQRT_2;
+    const GRADIENTS_2D: [[f64; 2]; 8] = [
+        [1., 0.],
+        [-1., 0.],
+        [0., 1.],
+        [0., -1.],
+        [DIAGONAL, DIAGONAL],
+        [-DIAGONAL, DIAGONAL],
+        [DIAGONAL, -DIAGONAL],
+        [-DIAGONAL, -DIAGONAL],
+    ];
+
+    let mut gradient = [0.; N];
+
+    match N {
+        1 => gradient[0] = to_signed_unit(hash),
+        2 => gradient.copy_from_slice(&GRADIENTS_2D[choose(hash, 8)]),
+        _ => {
+            // One of N positions for the zero, and 2^(N-1) sign combinations
+            // for the rest.
+            let signs = 1 << (N - 1);
+            let index = choose(hash, N * signs);
+            let zero = index / signs;
+
+            let mut bit = 0;
+            for (axis, component) in gradient.iter_mut().enumerate() {
+                if axis != zero {
+                    *component = if index & (1 << bit) == 0 { 1. } else { -1. };
+                    bit += 1;
+                }
+            }
+        }
+    }
+
+    gradient
+}
+
+/// Maps a hash uniformly onto `0..n`.
+fn choose(hash: u64, n: usize) -> usize {
+    ((hash as u128 * n as u128) >> 64) as usize
+}
+
+/// Maps a hash uniformly onto `[-1, 1)`.
+fn to_signed_unit(hash: u64) -> f64 {
+    (hash >> 11) as f64 * (1. / (1u64 << 52) as f64) - 1.
+}
+
+/// The largest coordinate the noise functions distinguish, 2^52.
+const MAX_COORDINATE: f64 = (1u64 << 52) as f64;
+
+/// Clamps each coordinate of a point to `±MAX_COORDINATE`, keeping the
+/// lattice arithmetic well clear of the `i64` limits.
+fn clamp<const N: usize>(point: [f64; N]) -> [f64; N] {
+    point.map(|x| x.clamp(-MAX_COORDINATE, MAX_COORDINATE))
+}
+
+fn abs(x: f64) -> f64 {
+    if x < 0. {
+        -x
+    } else {
+        x
+    }
+}
+
+/// The square root, calculated with Newton's method since `f64::sqrt`
+/// needs `std`.
+fn sqrt(x: f64) -> f64 {
+    if x <= 0. || x.is_infinite() {
+        return x.max(0.);
+    }
+
+    // Halving the exponent gives a guess within 6%, and each step doubles
+    // the number of correct digits.
+    let mut y = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..6 {
+        y = 0.5 * (y + x / y);
+    }
+
+    y
+}
+
+fn dot<const N: usize>(a: [f64; N], b: [f64; N]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Ken Perlin's quintic interpolation curve.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+/// Implements [`Noise`] for each given dimension by forwarding to a method.
+macro_rules! impl_noise {
+    ($ty:ident, $($n:literal => $method:ident),*) => {
+        $(
+            impl<H> $crate::noise::Noise<$n> for $ty<H>
+            where
+                H: core::hash::Hasher + Default + Clone,
+            {
+                fn get(&self, point: [f64; $n]) -> f64 {
+                    self.$method($crate::noise::clamp(point))
+                }
+            }
+        )*
+    };
+}
+
+use impl_noise;
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    /// Samples the noise on a grid of points with irrational-ish spacing,
+    /// checking that it stays within bounds and never jumps between
+    /// neighbouring points.
+    pub(super) fn check_coherent<const N: usize>(noise: &impl Noise<N>) {
+        let step = 1e-4;
+
+        for i in 0..4000 {
+            let mut point = [0.; N];
+            for (axis, x) in point.iter_mut().enumerate() {
+                *x = (i as f64 * 0.618034 * (axis + 1) as f64) % 23. - 11.5;
+            }
+
+            let value = noise.get(point);
+            assert!((-1.0..=1.0).contains(&value), "{value} at {point:?}");
+
+            for axis in 0..N {
+                let mut near = point;
+                near[axis] += step;
+
+                let change = abs(noise.get(near) - value);
+                assert!(change < step * 20., "jump of {change} at {point:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_matches_seed() {
+        let rng = PureRng::new(1234);
+
+        assert!(rng.seed_lattice([3, -4]) == rng.seed((3i64, -4i64)));
+        assert!(rng.seed_lattice([3]) == rng.seed(3i64));
+    }
+
+    #[test]
+    fn test_gradients() {
+        let rng = PureRng::new(1234);
+
+        for i in 0..100 {
+            let hash = rng.seed_lattice([i]).draw();
+
+            let g: [f64; 1] = gradient(hash);
+            assert!((-1.0..1.0).contains(&g[0]));
+
+            let g: [f64; 2] = gradient(hash);
+            assert!((dot(g, g) - 1.).abs() < 1e-12);
+
+            let g: [f64; 3] = gradient(hash);
+            assert_eq!(dot(g, g), 2.);
+
+            let g: [f64; 4] = gradient(hash);
+            assert_eq!(dot(g, g), 3.);
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for i in 0..1000 {
+            let x = i as f64 * 0.37 + 1e-3 * i as f64 * i as f64;
+            assert!((sqrt(x) - x.sqrt()).abs() <= x.sqrt() * 1e-15, "{x}");
+        }
+
+        assert_eq!(sqrt(0.), 0.);
+        assert_eq!(sqrt(4.), 2.);
+    }
+
+    #[test]
+    fn test_floor() {
+        assert_eq!(floor(1.5), 1);
+        assert_eq!(floor(-1.5), -2);
+        assert_eq!(floor(-2.), -2);
+        assert_eq!(floor(0.), 0);
+        assert_eq!(floor(-1e30), i64::MIN);
+        assert_eq!(floor(1e30), i64::MAX);
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        fn check<const N: usize>(noise: &impl Noise<N>) {
+            for x in [-1e30, -MAX_COORDINATE, MAX_COORDINATE, 1e30] {
+                let mut point = [0.5; N];
+                point[0] = x;
+
+                let value = noise.get(point);
+                assert!((-1.0..=1.0).contains(&value), "{value} at {point:?}");
+
+                let mut clamped = point;
+                clamped[0] = x.clamp(-MAX_COORDINATE, MAX_COORDINATE);
+                assert_eq!(value, noise.get(clamped));
+            }
+        }
+
+        let rng = PureRng::new(1234);
+        check::<1>(&ValueNoise::new(rng));
+        check::<2>(&ValueNoise::new(rng));
+        check::<2>(&Perlin::new(rng));
+        check::<4>(&Perlin::new(rng));
+        check::<1>(&OpenSimplex2::new(rng));
+        check::<2>(&OpenSimplex2::new(rng));
+        check::<3>(&OpenSimplex2::new(rng));
+        check::<4>(&OpenSimplex2::new(rng));
+        check::<2>(&Worley::new(rng));
+        check::<3>(&Fbm::new(Worley::new(rng)));
+
+        let site = Worley::new(rng).nearest_site([-1e30, 0.5]);
+        assert_eq!(site.cell[0], -(1 << 52));
+    }
+}
@@ -0,0 +1,110 @@
+use core::hash::{Hash, Hasher};
+
+use super::{dot, fade, floor, gradient, impl_noise, SeedNoise};
+use crate::PureRandomGenerator;
+
+/// Gradient noise, as described by Ken Perlin.
+///
+/// Each integer point `(x, y, ...)` is assigned a random gradient drawn from
+/// `rng.seed((x, y, ...))`, with the coordinates as `i64`s. The noise is zero
+/// at every lattice point, and follows the gradients in between.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Perlin<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+}
+
+impl<H> Perlin<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates Perlin noise with its gradients drawn from the given
+    /// generator.
+    pub fn new(rng: PureRandomGenerator<H>) -> Self {
+        Self { rng }
+    }
+
+    fn perlin<const N: usize>(&self, point: [f64; N]) -> f64 {
+        let mut base = [0; N];
+        let mut offsets = [0.; N];
+        let mut weights = [0.; N];
+        for axis in 0..N {
+            base[axis] = floor(point[axis]);
+            offsets[axis] = point[axis] - base[axis] as f64;
+            weights[axis] = fade(offsets[axis]);
+        }
+
+        // Blend the gradient contributions of the 2^N corners of the cell.
+        let mut value = 0.;
+        for corner in 0..1 << N {
+            let mut corner_point = base;
+            let mut corner_offsets = offsets;
+            let mut weight = 1.;
+            for axis in 0..N {
+                if corner & (1 << axis) == 0 {
+                    weight *= 1. - weights[axis];
+                } else {
+                    corner_point[axis] += 1;
+                    corner_offsets[axis] -= 1.;
+                    weight *= weights[axis];
+                }
+            }
+
+            value += weight
+                * dot(
+                    gradient(self.rng.seed_lattice(corner_point).draw()),
+                    corner_offsets,
+                );
+        }
+
+        // The noise is bounded by half the length of the gradients times the
+        // length of the cell diagonal, ie. |g|√N/2.
+        value
+            * match N {
+                1 => 2.,
+                2 => core::f64::consts::SQRT_2,
+                3 => 0.816496580927726,
+                _ => 0.5773502691896258,
+            }
+    }
+}
+
+impl<H> SeedNoise for Perlin<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn seed(&self, hashable: impl Hash) -> Self {
+        Self::new(self.rng.seed(hashable))
+    }
+}
+
+impl_noise!(Perlin, 1 => perlin, 2 => perlin, 3 => perlin, 4 => perlin);
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{noise::tests::check_coherent, noise::Noise, PureRng};
+
+    #[test]
+    fn test_zero_at_lattice_points() {
+        let noise = Perlin::new(PureRng::new(1234));
+
+        assert_eq!(noise.get([3.]), 0.);
+        assert_eq!(noise.get([3., -4.]), 0.);
+        assert_eq!(noise.get([3., -4., 5.]), 0.);
+        assert_eq!(noise.get([3., -4., 5., -6.]), 0.);
+        assert_ne!(noise.get([3.5, -4.5]), 0.);
+    }
+
+    #[test]
+    fn test_coherent() {
+        let noise = Perlin::new(PureRng::new(1234));
+
+        check_coherent::<1>(&noise);
+        check_coherent::<2>(&noise);
+        check_coherent::<3>(&noise);
+        check_coherent::<4>(&noise);
+    }
+}
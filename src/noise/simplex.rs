@@ -0,0 +1,290 @@
+use core::hash::{Hash, Hasher};
+
+use super::{dot, floor, gradient, impl_noise, SeedNoise};
+use crate::PureRandomGenerator;
+
+/// The squared radius of the kernels in 2 to 4 dimensions.
+const RADIUS_SQUARED: f64 = 0.5;
+
+const SKEW_2D: f64 = 0.366025403784439;
+const UNSKEW_2D: f64 = -0.21132486540518713;
+
+const ROTATE_3D: f64 = 2. / 3.;
+
+const SKEW_4D: f64 = -0.138196601125011;
+const UNSKEW_4D: f64 = 0.309016994374947;
+const LATTICE_STEP_4D: f64 = 0.2;
+
+// The largest possible raw values, used to scale the output to [-1, 1]. These
+// were found by searching for the point where the sum of the kernels is
+// greatest when every gradient points the best way, and rounded up.
+const MAX_1D: f64 = 0.31640625;
+const MAX_2D: f64 = 0.010081;
+const MAX_3D: f64 = 0.013008;
+const MAX_4D: f64 = 0.01593;
+
+/// OpenSimplex2 noise, as designed by K.jpg.
+///
+/// A gradient noise with fewer directional artifacts than [`Perlin`], built
+/// from overlapping radial kernels on a simplex lattice rather than by
+/// interpolating over a grid. The gradient for each lattice point is drawn
+/// from `rng.seed((x, y))` in 2D, where `x` and `y` are the point's `i64`
+/// coordinates in the skewed lattice. In 3D and 4D the lattice is made of
+/// several offset copies of a grid, so the index of the copy comes first, eg.
+/// `rng.seed((copy, x, y, z))`.
+///
+/// The kernels have a squared radius of 0.5 in every dimension. This is
+/// smaller than the 0.6 used in 3D and 4D by the reference implementation,
+/// which can reach lattice points that its search skips, but guarantees the
+/// noise is continuous.
+///
+/// OpenSimplex2 is only defined for 2 to 4 dimensions. In 1D this instead
+/// sums the same radial kernels centred on each integer.
+///
+/// [`Perlin`]: super::Perlin
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OpenSimplex2<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+}
+
+impl<H> OpenSimplex2<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates OpenSimplex2 noise with its gradients drawn from the given
+    /// generator.
+    pub fn new(rng: PureRandomGenerator<H>) -> Self {
+        Self { rng }
+    }
+
+    /// Returns the contribution of the given lattice point, which is at the
+    /// given offset from the point being sampled.
+    fn contribution<const N: usize, const P: usize>(
+        &self,
+        radius_squared: f64,
+        lattice_point: [i64; P],
+        offset: [f64; N],
+    ) -> f64 {
+        let a = radius_squared - dot(offset, offset);
+        if a <= 0. {
+            return 0.;
+        }
+
+        let gradient = gradient(self.rng.seed_lattice(lattice_point).draw());
+
+        let a = a * a;
+        a * a * dot(gradient, offset)
+    }
+
+    fn noise_1d(&self, [x]: [f64; 1]) -> f64 {
+        let base = floor(x);
+        let offset = x - base as f64;
+
+        let value = self.contribution(1., [base], [offset])
+            + self.contribution(1., [base + 1], [offset - 1.]);
+
+        value / MAX_1D
+    }
+
+    fn noise_2d(&self, [x, y]: [f64; 2]) -> f64 {
+        // Skew onto the lattice of squares, each made of two triangles.
+        let s = SKEW_2D * (x + y);
+        let (xs, ys) = (x + s, y + s);
+        let (xsb, ysb) = (floor(xs), floor(ys));
+        let (xi, yi) = (xs - xsb as f64, ys - ysb as f64);
+
+        // Unskew the offset from the square's origin.
+        let t = (xi + yi) * UNSKEW_2D;
+        let (dx0, dy0) = (xi + t, yi + t);
+
+        let mut value = self.contribution(RADIUS_SQUARED, [xsb, ysb], [dx0, dy0])
+            + self.contribution(
+                RADIUS_SQUARED,
+                [xsb + 1, ysb + 1],
+                [dx0 - (1. + 2. * UNSKEW_2D), dy0 - (1. + 2. * UNSKEW_2D)],
+            );
+
+        // The third corner depends on which triangle the point is in.
+        value += if dy0 > dx0 {
+            self.contribution(
+                RADIUS_SQUARED,
+                [xsb, ysb + 1],
+                [dx0 - UNSKEW_2D, dy0 - (1. + UNSKEW_2D)],
+            )
+        } else {
+            self.contribution(
+                RADIUS_SQUARED,
+                [xsb + 1, ysb],
+                [dx0 - (1. + UNSKEW_2D), dy0 - UNSKEW_2D],
+            )
+        };
+
+        value / MAX_2D
+    }
+
+    fn noise_3d(&self, [x, y, z]: [f64; 3]) -> f64 {
+        // Rotate so that the lattice's main diagonal doesn't line up with an
+        // axis.
+        let r = ROTATE_3D * (x + y + z);
+        let mut point = [r - x, r - y, r - z];
+
+        // The body-centred cubic lattice is made of two cubic grids, offset by
+        // half a cell. Start with the nearest point on the first grid.
+        let mut base = [0; 3];
+        let mut sign = [0.; 3];
+        for axis in 0..3 {
+            base[axis] = floor(point[axis] + 0.5);
+            point[axis] -= base[axis] as f64;
+            sign[axis] = if point[axis] >= 0. { -1. } else { 1. };
+        }
+
+        let mut value = 0.;
+        for copy in 0..2 {
+            value += self.contribution(RADIUS_SQUARED, [copy, base[0], base[1], base[2]], point);
+
+            // The next nearest point on this grid is a step along the axis
+            // with the largest offset.
+            let abs = point.map(super::abs);
+            let axis = if abs[0] >= abs[1] && abs[0] >= abs[2] {
+                0
+            } else if abs[1] > abs[0] && abs[1] >= abs[2] {
+                1
+            } else {
+                2
+            };
+            let mut next_base = [copy, base[0], base[1], base[2]];
+            next_base[axis + 1] -= sign[axis] as i64;
+            let mut next = point;
+            next[axis] += sign[axis];
+            value += self.contribution(RADIUS_SQUARED, next_base, next);
+
+            // Move to the nearest point on the second grid.
+            for axis in 0..3 {
+                if sign[axis] < 0. {
+                    base[axis] += 1;
+                }
+                point[axis] = sign[axis] * (0.5 - abs[axis]);
+                sign[axis] = -sign[axis];
+            }
+        }
+
+        value / MAX_3D
+    }
+
+    fn noise_4d(&self, [x, y, z, w]: [f64; 4]) -> f64 {
+        // Skew onto the lattice, which is made of five offset copies of a
+        // hypercubic grid.
+        let s = SKEW_4D * (x + y + z + w);
+        let skewed = [x + s, y + s, z + s, w + s];
+
+        let mut base = [0; 4];
+        let mut inner = [0.; 4];
+        for axis in 0..4 {
+            base[axis] = floor(skewed[axis]);
+            inner[axis] = skewed[axis] - base[axis] as f64;
+        }
+
+        // Start on the copy which the point is furthest along, and step
+        // through the rest.
+        let inner_sum: f64 = inner.iter().sum();
+        let starting_copy = (inner_sum * 1.25) as i64;
+        let starting_offset = starting_copy as f64 * -LATTICE_STEP_4D;
+        for x in &mut inner {
+            *x += starting_offset;
+        }
+        let mut unskew = (inner_sum + starting_offset * 4.) * UNSKEW_4D;
+        let mut copy = starting_copy;
+
+        let mut value = 0.;
+        for i in 0..5 {
+            // Pick the vertex of this copy's simplex nearest to the point.
+            let score = 1. + unskew * (-1. / UNSKEW_4D);
+            let [xi, yi, zi, wi] = inner;
+            let step = if xi >= yi && xi >= zi && xi >= wi && xi >= score {
+                Some(0)
+            } else if yi > xi && yi >= zi && yi >= wi && yi >= score {
+                Some(1)
+            } else if zi > xi && zi > yi && zi >= wi && zi >= score {
+                Some(2)
+            } else if wi > xi && wi > yi && wi > zi && wi >= score {
+                Some(3)
+            } else {
+                None
+            };
+            if let Some(axis) = step {
+                base[axis] += 1;
+                inner[axis] -= 1.;
+                unskew -= UNSKEW_4D;
+            }
+
+            let offset = inner.map(|x| x + unskew);
+            value += self.contribution(
+                RADIUS_SQUARED,
+                [copy, base[0], base[1], base[2], base[3]],
+                offset,
+            );
+
+            if i == 4 {
+                break;
+            }
+
+            for x in &mut inner {
+                *x += LATTICE_STEP_4D;
+            }
+            unskew += LATTICE_STEP_4D * 4. * UNSKEW_4D;
+            copy -= 1;
+
+            if i == starting_copy {
+                for b in &mut base {
+                    *b -= 1;
+                }
+                copy += 5;
+            }
+        }
+
+        value / MAX_4D
+    }
+}
+
+impl<H> SeedNoise for OpenSimplex2<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn seed(&self, hashable: impl Hash) -> Self {
+        Self::new(self.rng.seed(hashable))
+    }
+}
+
+impl_noise!(OpenSimplex2, 1 => noise_1d, 2 => noise_2d, 3 => noise_3d, 4 => noise_4d);
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{noise::tests::check_coherent, noise::Noise, PureRng};
+
+    #[test]
+    fn test_coherent() {
+        let noise = OpenSimplex2::new(PureRng::new(1234));
+
+        check_coherent::<1>(&noise);
+        check_coherent::<2>(&noise);
+        check_coherent::<3>(&noise);
+        check_coherent::<4>(&noise);
+    }
+
+    #[test]
+    fn test_seed() {
+        let noise = OpenSimplex2::new(PureRng::new(1234));
+        let point = [0.3, 1.7, -2.2];
+
+        assert_eq!(noise.get(point), noise.get(point));
+        assert_ne!(noise.get(point), noise.seed("other").get(point));
+        assert_eq!(
+            noise.seed("other").get(point),
+            OpenSimplex2::new(PureRng::new(1234).seed("other")).get(point)
+        );
+    }
+}
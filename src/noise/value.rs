@@ -0,0 +1,95 @@
+use core::hash::{Hash, Hasher};
+
+use super::{fade, floor, impl_noise, lattice_value, SeedNoise};
+use crate::PureRandomGenerator;
+
+/// Value noise: a random value at every lattice point, smoothly interpolated
+/// between them.
+///
+/// The value at each integer point `(x, y, ...)` is drawn from
+/// `rng.seed((x, y, ...))`, with the coordinates as `i64`s. It's the cheapest
+/// of the noise functions, but looks blockier than gradient noise.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ValueNoise<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+}
+
+impl<H> ValueNoise<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates value noise with its lattice values drawn from the given
+    /// generator.
+    pub fn new(rng: PureRandomGenerator<H>) -> Self {
+        Self { rng }
+    }
+
+    fn value<const N: usize>(&self, point: [f64; N]) -> f64 {
+        let mut base = [0; N];
+        let mut weights = [0.; N];
+        for axis in 0..N {
+            base[axis] = floor(point[axis]);
+            weights[axis] = fade(point[axis] - base[axis] as f64);
+        }
+
+        // Blend the values at the 2^N corners of the cell.
+        let mut value = 0.;
+        for corner in 0..1 << N {
+            let mut corner_point = base;
+            let mut weight = 1.;
+            for axis in 0..N {
+                if corner & (1 << axis) == 0 {
+                    weight *= 1. - weights[axis];
+                } else {
+                    corner_point[axis] += 1;
+                    weight *= weights[axis];
+                }
+            }
+
+            value += weight * lattice_value(&self.rng, corner_point);
+        }
+
+        value
+    }
+}
+
+impl<H> SeedNoise for ValueNoise<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn seed(&self, hashable: impl Hash) -> Self {
+        Self::new(self.rng.seed(hashable))
+    }
+}
+
+impl_noise!(ValueNoise, 1 => value, 2 => value, 3 => value, 4 => value);
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{noise::tests::check_coherent, noise::Noise, PureRng};
+
+    #[test]
+    fn test_lattice_values() {
+        let rng = PureRng::new(1234);
+        let noise = ValueNoise::new(rng);
+
+        let mut lattice_rng = rng.seed((3i64, -4i64));
+        let expected = (lattice_rng.draw() >> 11) as f64 / (1u64 << 52) as f64 - 1.;
+
+        assert_eq!(noise.get([3., -4.]), expected);
+    }
+
+    #[test]
+    fn test_coherent() {
+        let noise = ValueNoise::new(PureRng::new(1234));
+
+        check_coherent::<1>(&noise);
+        check_coherent::<2>(&noise);
+        check_coherent::<3>(&noise);
+        check_coherent::<4>(&noise);
+    }
+}
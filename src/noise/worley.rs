@@ -0,0 +1,419 @@
+use core::hash::{Hash, Hasher};
+
+use super::{abs, clamp, floor, sqrt, SeedNoise};
+use crate::PureRandomGenerator;
+
+/// How [`Worley`] noise measures the distance between points.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Distance {
+    /// Straight-line distance, giving rounded cells.
+    Euclidean,
+    /// The sum of the distances along each axis, giving diamond-shaped cells.
+    Manhattan,
+    /// The largest distance along any axis, giving square cells.
+    Chebyshev,
+}
+
+/// Which distances [`Worley`] noise returns.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WorleyOutput {
+    /// The distance to the nearest site, dark at the sites and bright at the
+    /// cell edges.
+    F1,
+    /// The distance to the second nearest site.
+    F2,
+    /// The difference between the two, which is 0 along the cell edges and
+    /// looks like cracks or cell walls.
+    F2MinusF1,
+}
+
+/// Worley noise, also known as cellular noise, and the Voronoi cells it's
+/// built from.
+///
+/// Every unit cell of the integer grid holds one site, at a random position
+/// drawn from `rng.seed((x, y))` in 2D, where `x` and `y` are the cell's
+/// `i64` coordinates. The noise is the distance to the nearest site, the
+/// second nearest, or their difference, depending on
+/// [`output`](Self::output).
+///
+/// As [`Noise`](super::Noise), a distance `d` is mapped onto `[-1, 1]` as
+/// `2d - 1`, and clamped. [`distances`](Self::distances) gives the raw
+/// distances, and [`nearest_site`](Self::nearest_site) finds the Voronoi
+/// cell containing a point, eg. to look up its biome.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{
+///     noise::{Distance, Noise, Worley, WorleyOutput},
+///     PureRng,
+/// };
+///
+/// let mut cracks = Worley::new(PureRng::new("cracks"));
+/// cracks.distance = Distance::Manhattan;
+/// cracks.output = WorleyOutput::F2MinusF1;
+///
+/// let value = cracks.get([1.5, -2.25]);
+/// assert!((-1.0..=1.0).contains(&value));
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Worley<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+    /// How distances are measured. Defaults to [`Distance::Euclidean`].
+    pub distance: Distance,
+    /// Which distances the noise returns. Defaults to [`WorleyOutput::F1`].
+    pub output: WorleyOutput,
+}
+
+/// The site nearest to a point, returned by [`Worley::nearest_site`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Site<H, const N: usize>
+where
+    H: Hasher + Default + Clone,
+{
+    /// The integer coordinates of the grid cell holding the site.
+    pub cell: [i64; N],
+    /// The position of the site.
+    pub position: [f64; N],
+    /// The distance from the query point to the site.
+    pub distance: f64,
+    /// The generator seeded with the cell's coordinates, ie.
+    /// `rng.seed((x, y))` in 2D. Its first draws give the site's position,
+    /// so seed it again for other values, eg. `site.rng.seed("temperature")`.
+    pub rng: PureRandomGenerator<H>,
+}
+
+impl<H> Worley<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates Worley noise with its sites drawn from the given generator,
+    /// returning the Euclidean distance to the nearest site.
+    pub fn new(rng: PureRandomGenerator<H>) -> Self {
+        Self {
+            rng,
+            distance: Distance::Euclidean,
+            output: WorleyOutput::F1,
+        }
+    }
+
+    /// Returns the site nearest to the given point, ie. the Voronoi cell
+    /// containing it. Coordinates beyond `±2^52` are clamped, as described in
+    /// the [module documentation](super).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the point isn't finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    /// # {
+    /// use pure_rng::{noise::Worley, PureRng};
+    ///
+    /// let biomes = Worley::new(PureRng::new("biomes"));
+    ///
+    /// let site = biomes.nearest_site([0.01 * 1234., 0.01 * 5678.]);
+    /// let temperature = site.rng.seed("temperature").gen_range(-10.0..30.0);
+    /// assert!((-10.0..30.0).contains(&temperature));
+    /// assert_eq!(biomes.nearest_site(site.position).cell, site.cell);
+    /// # }
+    /// ```
+    pub fn nearest_site<const N: usize>(&self, point: [f64; N]) -> Site<H, N> {
+        assert!(
+            point.iter().all(|x| x.is_finite()),
+            "the point must be finite"
+        );
+        let nearest = self.search(clamp(point)).0;
+
+        Site {
+            cell: nearest.cell,
+            position: nearest.position,
+            distance: self.finish(nearest.measure),
+            rng: self.rng.seed_lattice(nearest.cell),
+        }
+    }
+
+    /// Returns the distances from the given point to the nearest and second
+    /// nearest sites, ie. F1 and F2. They're NaN if the point isn't finite.
+    pub fn distances<const N: usize>(&self, point: [f64; N]) -> (f64, f64) {
+        if !point.iter().all(|x| x.is_finite()) {
+            return (f64::NAN, f64::NAN);
+        }
+
+        let (nearest, second) = self.search(clamp(point));
+
+        (self.finish(nearest.measure), self.finish(second.measure))
+    }
+
+    /// Returns the site in the given cell.
+    fn site<const N: usize>(&self, cell: [i64; N]) -> [f64; N] {
+        let mut rng = self.rng.seed_lattice(cell);
+
+        cell.map(|x| x as f64 + rng.draw_unit())
+    }
+
+    /// Finds the two nearest sites.
+    ///
+    /// Each site lies inside its cell, so no site is nearer than the nearest
+    /// point of its cell. The search visits rings of cells outwards from the
+    /// point's cell, skipping cells which can't hold anything nearer than the
+    /// second nearest site found so far, and stops at the first ring where
+    /// every cell can be skipped.
+    fn search<const N: usize>(&self, point: [f64; N]) -> (Nearest<N>, Nearest<N>) {
+        let base = point.map(floor);
+        let fraction: [f64; N] = core::array::from_fn(|axis| point[axis] - base[axis] as f64);
+        let edge = fraction
+            .iter()
+            .fold(1., |edge: f64, &x| edge.min(x).min(1. - x));
+
+        let mut nearest = Nearest::NONE;
+        let mut second = Nearest::NONE;
+
+        for ring in 0.. {
+            let gap = if ring == 0 {
+                0.
+            } else {
+                (ring - 1) as f64 + edge
+            };
+            if N == 0 || self.measure([gap]) >= second.measure {
+                break;
+            }
+
+            for_each_in_ring::<N>(ring, |offset| {
+                let gaps: [f64; N] = core::array::from_fn(|axis| match offset[axis] {
+                    0 => 0.,
+                    x if x > 0 => x as f64 - fraction[axis],
+                    x => fraction[axis] - (x + 1) as f64,
+                });
+                if self.measure(gaps) >= second.measure {
+                    return;
+                }
+
+                let mut cell = base;
+                for axis in 0..N {
+                    cell[axis] += offset[axis];
+                }
+
+                let position = self.site(cell);
+                let measure = self.measure::<N>(core::array::from_fn(|axis| {
+                    abs(point[axis] - position[axis])
+                }));
+                let site = Nearest {
+                    cell,
+                    position,
+                    measure,
+                };
+
+                if measure < nearest.measure {
+                    second = nearest;
+                    nearest = site;
+                } else if measure < second.measure {
+                    second = site;
+                }
+            });
+        }
+
+        (nearest, second)
+    }
+
+    /// Returns a value increasing with the distance given by its components
+    /// along each axis, which is the squared distance for
+    /// [`Distance::Euclidean`].
+    fn measure<const N: usize>(&self, deltas: [f64; N]) -> f64 {
+        let deltas = deltas.into_iter();
+
+        match self.distance {
+            Distance::Euclidean => deltas.map(|delta| delta * delta).sum(),
+            Distance::Manhattan => deltas.sum(),
+            Distance::Chebyshev => deltas.fold(0., f64::max),
+        }
+    }
+
+    /// Turns a [`measure`](Self::measure) into a distance.
+    fn finish(&self, measure: f64) -> f64 {
+        match self.distance {
+            Distance::Euclidean => sqrt(measure),
+            Distance::Manhattan | Distance::Chebyshev => measure,
+        }
+    }
+
+    fn value<const N: usize>(&self, point: [f64; N]) -> f64 {
+        let (f1, f2) = self.distances(point);
+        let distance = match self.output {
+            WorleyOutput::F1 => f1,
+            WorleyOutput::F2 => f2,
+            WorleyOutput::F2MinusF1 => f2 - f1,
+        };
+
+        let value = 2. * distance - 1.;
+        // Unlike `min`, this keeps NaN.
+        if value > 1. {
+            1.
+        } else {
+            value
+        }
+    }
+}
+
+/// A site found by [`Worley::search`].
+#[derive(Copy, Clone)]
+struct Nearest<const N: usize> {
+    cell: [i64; N],
+    position: [f64; N],
+    measure: f64,
+}
+
+impl<const N: usize> Nearest<N> {
+    const NONE: Self = Self {
+        cell: [0; N],
+        position: [0.; N],
+        measure: f64::INFINITY,
+    };
+}
+
+/// Calls `f` with every offset whose largest component is `ring`, in a fixed
+/// order.
+fn for_each_in_ring<const N: usize>(ring: i64, mut f: impl FnMut([i64; N])) {
+    let mut offset = [-ring; N];
+    loop {
+        if offset.iter().any(|&x| x == ring || x == -ring) || ring == 0 {
+            f(offset);
+        }
+
+        // Count through the cube like an odometer.
+        let mut axis = 0;
+        while offset[axis] == ring {
+            offset[axis] = -ring;
+            axis += 1;
+            if axis == N {
+                return;
+            }
+        }
+        offset[axis] += 1;
+    }
+}
+
+impl<H> SeedNoise for Worley<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn seed(&self, hashable: impl Hash) -> Self {
+        Self {
+            rng: self.rng.seed(hashable),
+            ..*self
+        }
+    }
+}
+
+super::impl_noise!(Worley, 1 => value, 2 => value, 3 => value, 4 => value);
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{noise::tests::check_coherent, PureRng};
+
+    const DISTANCES: [Distance; 3] = [
+        Distance::Euclidean,
+        Distance::Manhattan,
+        Distance::Chebyshev,
+    ];
+
+    /// Finds the two nearest sites by checking a wide block of cells.
+    fn brute_force(noise: &Worley<impl Hasher + Default + Clone>, point: [f64; 2]) -> (f64, f64) {
+        let mut measures = Vec::new();
+        for x in -4..=4 {
+            for y in -4..=4 {
+                let site = noise.site([floor(point[0]) + x, floor(point[1]) + y]);
+                measures.push(noise.measure([abs(point[0] - site[0]), abs(point[1] - site[1])]));
+            }
+        }
+        measures.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        (noise.finish(measures[0]), noise.finish(measures[1]))
+    }
+
+    #[test]
+    fn test_distances() {
+        for distance in DISTANCES {
+            let noise = Worley {
+                distance,
+                ..Worley::new(PureRng::new("worley"))
+            };
+
+            for i in 0..500 {
+                let point = [
+                    i as f64 * 0.618034 % 17. - 8.5,
+                    i as f64 * 0.414214 % 13. - 6.5,
+                ];
+                assert_eq!(noise.distances(point), brute_force(&noise, point));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nearest_site() {
+        let rng = PureRng::new("worley");
+        let noise = Worley::new(rng);
+
+        for i in 0..100 {
+            let point = [i as f64 * 0.618034 % 7., i as f64 * 0.414214 % 5., 0.5];
+            let site = noise.nearest_site(point);
+
+            assert!(site.rng == rng.seed((site.cell[0], site.cell[1], site.cell[2])));
+            assert_eq!(site.position, noise.site(site.cell));
+            assert_eq!(site.distance, noise.distances(point).0);
+            for axis in 0..3 {
+                assert_eq!(floor(site.position[axis]), site.cell[axis]);
+            }
+        }
+
+        let site = noise.nearest_site([2.5, 2.5]);
+        assert_eq!(noise.nearest_site(site.position).cell, site.cell);
+        assert_eq!(noise.nearest_site(site.position).distance, 0.);
+    }
+
+    #[test]
+    fn test_coherent() {
+        let noise = Worley::new(PureRng::new(1234));
+
+        for distance in DISTANCES {
+            // F2 - F1 depends on both of the others.
+            let noise = Worley {
+                distance,
+                output: WorleyOutput::F2MinusF1,
+                ..noise
+            };
+
+            check_coherent::<1>(&noise);
+            check_coherent::<2>(&noise);
+            check_coherent::<3>(&noise);
+        }
+
+        check_coherent::<2>(&Worley {
+            output: WorleyOutput::F2,
+            ..noise
+        });
+        check_coherent::<4>(&noise);
+    }
+
+    #[test]
+    fn test_ring() {
+        let mut offsets = Vec::new();
+        for ring in 0..3 {
+            for_each_in_ring::<2>(ring, |offset| offsets.push(offset));
+        }
+
+        assert_eq!(offsets.len(), 25);
+        offsets.sort();
+        offsets.dedup();
+        assert_eq!(offsets.len(), 25);
+    }
+}
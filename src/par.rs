@@ -0,0 +1,251 @@
+//! Parallel generation with `rayon`, enabled by the `rayon` feature.
+//!
+//! Every `seed()` call produces an independent generator, so generating values
+//! for a large number of seeds is embarrassingly parallel. The functions here
+//! are guaranteed to produce results bit-identical to their sequential
+//! equivalents, regardless of the number of threads.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+//! # {
+//! use pure_rng::PureRng;
+//! use rayon::prelude::*;
+//!
+//! let rng = PureRng::new("world").seed("entity health");
+//! let health: Vec<u32> = rng
+//!     .par_seed_map(0..100_000u64, |rng| rng.gen_range(10..20))
+//!     .collect();
+//!
+//! assert_eq!(health[1234], rng.seed(1234u64).gen_range(10..20));
+//! # }
+//! ```
+
+use core::hash::{Hash, Hasher};
+
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+use rayon::iter::IndexedParallelIterator;
+use rayon::iter::{IntoParallelIterator, Map, ParallelIterator};
+
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+use crate::seq::IteratorPureRandom;
+use crate::PureRandomGenerator;
+
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone + Send + Sync,
+{
+    /// Maps each of the given seeds to a value in parallel, passing the
+    /// function the generator seeded with it.
+    ///
+    /// This is the parallel equivalent of
+    /// `seeds.into_iter().map(|seed| f(rng.seed(seed)))`. The resulting
+    /// iterator is indexed if the seeds are, eg. for a range of `usize` or
+    /// `u32`, and collecting it preserves the order of the seeds.
+    pub fn par_seed_map<I, F, T>(
+        &self,
+        seeds: I,
+        f: F,
+    ) -> Map<I::Iter, impl Fn(I::Item) -> T + Send + Sync>
+    where
+        I: IntoParallelIterator,
+        I::Item: Hash,
+        F: Fn(Self) -> T + Send + Sync,
+        T: Send,
+    {
+        let rng = self.clone();
+
+        seeds.into_par_iter().map(move |seed| f(rng.seed(seed)))
+    }
+}
+
+/// Parallel versions of the [`IteratorPureRandom`] functions. Implemented on
+/// all indexed parallel iterators.
+///
+/// The elements to choose are selected exactly as the sequential functions
+/// would select them from an iterator of the same length, so the results are
+/// identical to calling the [`IteratorPureRandom`] functions on the equivalent
+/// `ExactSizeIterator`. Only the chosen elements are collected, but every
+/// element is still produced.
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+pub trait ParallelIteratorPureRandom<H>: IndexedParallelIterator
+where
+    H: Hasher + Default + Clone,
+{
+    /// Choose one element at random from the iterator.
+    ///
+    /// See [`IteratorPureRandom::choose`].
+    fn choose(self, rng: PureRandomGenerator<H>) -> Option<Self::Item> {
+        let index = IteratorPureRandom::choose(0..self.len(), rng)?;
+
+        self.skip(index).take(1).collect::<Vec<_>>().pop()
+    }
+
+    /// Choose one element at random from the iterator.
+    ///
+    /// See [`IteratorPureRandom::choose_stable`].
+    fn choose_stable(self, rng: PureRandomGenerator<H>) -> Option<Self::Item> {
+        let index = IteratorPureRandom::choose_stable(0..self.len(), rng)?;
+
+        self.skip(index).take(1).collect::<Vec<_>>().pop()
+    }
+
+    /// Collects values at random from the iterator into a supplied buffer
+    /// until that buffer is filled.
+    ///
+    /// See [`IteratorPureRandom::choose_multiple_fill`].
+    fn choose_multiple_fill(self, rng: PureRandomGenerator<H>, buf: &mut [Self::Item]) -> usize {
+        let mut indices = vec![0; buf.len()];
+        let len = IteratorPureRandom::choose_multiple_fill(0..self.len(), rng, &mut indices);
+        indices.truncate(len);
+
+        for (slot, item) in buf.iter_mut().zip(select(self, &indices)) {
+            *slot = item;
+        }
+
+        len
+    }
+
+    /// Collects `amount` values at random from the iterator into a vector.
+    ///
+    /// See [`IteratorPureRandom::choose_multiple`].
+    fn choose_multiple(self, rng: PureRandomGenerator<H>, amount: usize) -> Vec<Self::Item> {
+        let indices = IteratorPureRandom::choose_multiple(0..self.len(), rng, amount);
+
+        select(self, &indices)
+    }
+}
+
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+impl<I, H> ParallelIteratorPureRandom<H> for I
+where
+    I: IndexedParallelIterator,
+    H: Hasher + Default + Clone,
+{
+}
+
+/// Collects the elements at the given indices, in the order the indices are
+/// given.
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+fn select<I: IndexedParallelIterator>(iter: I, indices: &[usize]) -> Vec<I::Item> {
+    let mut order: Vec<(usize, usize)> = indices
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| (index, position))
+        .collect();
+    order.sort_unstable();
+
+    let mut selected: Vec<(usize, I::Item)> = iter
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let found = order.binary_search_by_key(&index, |&(index, _)| index);
+            found.ok().map(|i| (order[i].1, item))
+        })
+        .collect();
+    selected.sort_unstable_by_key(|&(position, _)| position);
+
+    selected.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(all(
+    test,
+    feature = "rapidhash",
+    any(feature = "rand_08", feature = "rand_09")
+))]
+mod tests {
+    use rayon::{prelude::*, ThreadPoolBuilder};
+
+    use super::*;
+    use crate::PureRng;
+
+    /// Runs the given function on thread pools of various sizes, checking the
+    /// result is always the same.
+    fn on_threads<T: PartialEq + core::fmt::Debug + Send>(f: impl Fn() -> T + Send + Sync) -> T {
+        let results: Vec<T> = [1, 2, 7]
+            .into_iter()
+            .map(|threads| {
+                let pool = ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap();
+                pool.install(&f)
+            })
+            .collect();
+
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]));
+        results.into_iter().next().unwrap()
+    }
+
+    fn value(rng: PureRng) -> u64 {
+        rng.seed("value").seed(7).draw()
+    }
+
+    #[test]
+    fn test_par_seed_map() {
+        let rng = PureRng::new(1234);
+
+        let parallel = on_threads(|| rng.par_seed_map(0..10_000usize, value).collect::<Vec<_>>());
+        let sequential: Vec<u64> = (0..10_000usize).map(|i| value(rng.seed(i))).collect();
+        assert_eq!(parallel, sequential);
+
+        let seeds = vec!["a", "b", "c"];
+        let parallel: Vec<u64> = rng.par_seed_map(seeds.par_iter(), value).collect();
+        let sequential: Vec<u64> = seeds.iter().map(|seed| value(rng.seed(seed))).collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_choose() {
+        let rng = PureRng::new(1234);
+        let items: Vec<u32> = (0..5000).collect();
+
+        for i in 0..20 {
+            let rng = rng.seed(i);
+
+            assert_eq!(
+                on_threads(|| ParallelIteratorPureRandom::choose(items.par_iter(), rng)),
+                IteratorPureRandom::choose(items.iter(), rng),
+            );
+            assert_eq!(
+                on_threads(|| ParallelIteratorPureRandom::choose_stable(items.par_iter(), rng)),
+                IteratorPureRandom::choose_stable(items.iter(), rng),
+            );
+        }
+
+        let empty: [u32; 0] = [];
+        assert_eq!(
+            ParallelIteratorPureRandom::choose(empty.par_iter(), rng),
+            None
+        );
+    }
+
+    #[test]
+    fn test_choose_multiple() {
+        let rng = PureRng::new(1234);
+        let items: Vec<u32> = (0..5000).collect();
+
+        for amount in [0, 1, 10, 5000, 6000] {
+            let rng = rng.seed(amount);
+
+            assert_eq!(
+                on_threads(|| ParallelIteratorPureRandom::choose_multiple(
+                    items.par_iter(),
+                    rng,
+                    amount
+                )),
+                IteratorPureRandom::choose_multiple(items.iter(), rng, amount),
+            );
+        }
+
+        let mut parallel = [0; 10];
+        let mut sequential = [0; 10];
+        ParallelIteratorPureRandom::choose_multiple_fill(
+            items.par_iter().copied(),
+            rng,
+            &mut parallel,
+        );
+        IteratorPureRandom::choose_multiple_fill(items.iter().copied(), rng, &mut sequential);
+        assert_eq!(parallel, sequential);
+    }
+}
@@ -0,0 +1,305 @@
+//! Random-access permutations.
+
+use core::hash::Hasher;
+
+use crate::{hashers::mix, PureRandomGenerator};
+
+/// The number of Feistel rounds.
+const ROUNDS: usize = 8;
+
+/// A random permutation of `0..len`, computed one index at a time.
+///
+/// `SlicePureRandom::shuffle` and `seq::index::sample` materialise the whole
+/// permutation. This instead maps any index to its permuted position, and
+/// back, in constant expected time and without allocating, for any length up
+/// to `u64::MAX`. That makes it suitable for a stable random ordering of
+/// billions of IDs, lazily iterating a shuffle, or choosing a few items from
+/// a huge collection.
+///
+/// The permutation is a balanced Feistel network over the smallest even
+/// number of bits covering `len`, with round keys drawn from the generator.
+/// Outputs past the end are fed back in until one lands inside the range,
+/// which takes fewer than four passes on average.
+///
+/// The permutation isn't cryptographically secure. Its values are covered by
+/// the value-stability policy described on
+/// [`STREAM_VERSION`](crate::STREAM_VERSION).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::PureRng;
+///
+/// let order = PureRng::new("users").permutation(1_000_000_000);
+///
+/// let position = order.get(123_456_789);
+/// assert_eq!(order.index_of(position), 123_456_789);
+///
+/// // Pick three distinct items without shuffling the slice.
+/// let items: Vec<u32> = (0..100).collect();
+/// let chosen: Vec<&u32> = PureRng::new("prize")
+///     .permutation(items.len() as u64)
+///     .shuffled(&items)
+///     .take(3)
+///     .collect();
+/// assert_eq!(chosen.len(), 3);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PurePermutation {
+    len: u64,
+    half_bits: u32,
+    keys: [u64; ROUNDS],
+}
+
+impl PurePermutation {
+    /// Creates a permutation of `0..len`, keyed by the generator.
+    pub fn new<H>(len: u64, mut rng: PureRandomGenerator<H>) -> Self
+    where
+        H: Hasher + Default + Clone,
+    {
+        let bits = u64::BITS - len.saturating_sub(1).leading_zeros();
+
+        Self {
+            len,
+            half_bits: bits.div_ceil(2).max(1),
+            keys: core::array::from_fn(|_| rng.draw()),
+        }
+    }
+
+    /// The number of indices in the permutation.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the permutation is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the position `index` is permuted to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below `len`.
+    pub fn get(&self, index: u64) -> u64 {
+        assert!(index < self.len, "index out of range");
+
+        let mut value = self.encrypt(index);
+        while value >= self.len {
+            value = self.encrypt(value);
+        }
+
+        value
+    }
+
+    /// Returns the index which is permuted to `position`. The inverse of
+    /// [`get`](Self::get).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is not below `len`.
+    pub fn index_of(&self, position: u64) -> u64 {
+        assert!(position < self.len, "position out of range");
+
+        let mut value = self.decrypt(position);
+        while value >= self.len {
+            value = self.decrypt(value);
+        }
+
+        value
+    }
+
+    /// Iterates over the permuted positions of `0..len`, in order of index.
+    pub fn iter(&self) -> PermutationIter<'_> {
+        PermutationIter {
+            permutation: self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    /// Iterates over the items of a slice in permuted order, without
+    /// shuffling it. Taking the first few items chooses them without
+    /// replacement.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice's length is not `len`.
+    pub fn shuffled<'a, T>(&self, slice: &'a [T]) -> impl DoubleEndedIterator<Item = &'a T> + 'a {
+        assert_eq!(
+            slice.len() as u64,
+            self.len,
+            "slice length doesn't match the permutation"
+        );
+
+        let permutation = *self;
+        (0..self.len).map(move |index| &slice[permutation.get(index) as usize])
+    }
+
+    fn mask(&self) -> u64 {
+        (1 << self.half_bits) - 1
+    }
+
+    /// One pass of the Feistel network over the whole domain.
+    fn encrypt(&self, value: u64) -> u64 {
+        let mask = self.mask();
+        let (mut left, mut right) = (value >> self.half_bits, value & mask);
+
+        for key in self.keys {
+            (left, right) = (right, left ^ (mix(right ^ key) & mask));
+        }
+
+        (left << self.half_bits) | right
+    }
+
+    /// The inverse of [`encrypt`](Self::encrypt).
+    fn decrypt(&self, value: u64) -> u64 {
+        let mask = self.mask();
+        let (mut left, mut right) = (value >> self.half_bits, value & mask);
+
+        for key in self.keys.into_iter().rev() {
+            (left, right) = (right ^ (mix(left ^ key) & mask), left);
+        }
+
+        (left << self.half_bits) | right
+    }
+}
+
+impl<'a> IntoIterator for &'a PurePermutation {
+    type Item = u64;
+    type IntoIter = PermutationIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the positions of a [`PurePermutation`], created by
+/// [`PurePermutation::iter`].
+#[derive(Debug, Clone)]
+pub struct PermutationIter<'a> {
+    permutation: &'a PurePermutation,
+    front: u64,
+    back: u64,
+}
+
+impl Iterator for PermutationIter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.permutation.get(self.front - 1))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.back - self.front).ok();
+
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+impl DoubleEndedIterator for PermutationIter<'_> {
+    fn next_back(&mut self) -> Option<u64> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.permutation.get(self.back))
+    }
+}
+
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a random-access permutation of `0..len`. See
+    /// [`PurePermutation`].
+    pub fn permutation(self, len: u64) -> PurePermutation {
+        PurePermutation::new(len, self)
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use crate::PureRng;
+
+    #[test]
+    fn test_bijection() {
+        for len in [0, 1, 2, 3, 4, 5, 7, 16, 100, 1000, 4097] {
+            let permutation = PureRng::new(len).permutation(len);
+            let mut seen = vec![false; len as usize];
+
+            for (index, position) in permutation.iter().enumerate() {
+                assert!(!seen[position as usize]);
+                seen[position as usize] = true;
+                assert_eq!(permutation.index_of(position), index as u64);
+            }
+
+            assert!(seen.iter().all(|&seen| seen));
+        }
+    }
+
+    #[test]
+    fn test_large() {
+        for len in [1 << 40, u64::MAX - 1, u64::MAX] {
+            let permutation = PureRng::new(len).permutation(len);
+
+            for index in [0, 1, 12345, len / 2, len - 1] {
+                let position = permutation.get(index);
+                assert!(position < len);
+                assert_eq!(permutation.index_of(position), index);
+            }
+        }
+    }
+
+    #[test]
+    fn test_uniform() {
+        // Where index 0 ends up, over many permutations.
+        let mut counts = [0; 10];
+        for seed in 0..10_000u32 {
+            counts[PureRng::new(seed).permutation(10).get(0) as usize] += 1;
+        }
+
+        assert!(
+            counts.iter().all(|&count| (850..1150).contains(&count)),
+            "{counts:?}"
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let permutation = PureRng::new("iter").permutation(50);
+        let forward: Vec<u64> = permutation.iter().collect();
+
+        let mut backward: Vec<u64> = permutation.iter().rev().collect();
+        backward.reverse();
+        assert_eq!(backward, forward);
+
+        let mut iter = permutation.iter();
+        assert_eq!(iter.nth(10), Some(forward[10]));
+        assert_eq!(iter.next(), Some(forward[11]));
+        assert_eq!(iter.size_hint(), (38, Some(38)));
+        assert_eq!(iter.nth(100), None);
+
+        let items: Vec<char> = ('a'..='z').collect();
+        let permutation = PureRng::new("letters").permutation(26);
+        let mut shuffled: Vec<char> = permutation.shuffled(&items).copied().collect();
+        assert_ne!(shuffled, items);
+        shuffled.sort();
+        assert_eq!(shuffled, items);
+    }
+}
@@ -0,0 +1,138 @@
+//! Adapters between [`PureDistribution`] and the `rand` 0.8 [`Distribution`]
+//! trait.
+
+use core::{hash::Hasher, marker::PhantomData};
+
+use rand::{distributions::Distribution, Rng};
+
+use crate::{distributions::PureDistribution, PureRandomGenerator};
+
+/// Adapts any [`Distribution`] into a [`PureDistribution`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{rand_08::distributions::FromDistribution, PureRng};
+/// use rand::distributions::Standard;
+///
+/// let value: u64 = PureRng::new(1234).sample_pure(FromDistribution(Standard));
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct FromDistribution<D>(pub D);
+
+impl<T, D> PureDistribution<T> for FromDistribution<D>
+where
+    D: Distribution<T>,
+{
+    fn sample<H>(&self, rng: PureRandomGenerator<H>) -> T
+    where
+        H: Hasher + Default + Clone,
+    {
+        rng.sample(&self.0)
+    }
+}
+
+/// Adapts a [`PureDistribution`] into a [`Distribution`], so that it can be
+/// used with any [`Rng`].
+///
+/// A single `u64` is drawn from the `Rng` and used to seed a fresh
+/// [`PureRandomGenerator<H>`], which is then passed to the wrapped
+/// distribution.
+pub struct IntoDistribution<D, H> {
+    distr: D,
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<D, H> IntoDistribution<D, H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Wraps the given [`PureDistribution`].
+    pub fn new(distr: D) -> Self {
+        Self {
+            distr,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped [`PureDistribution`].
+    pub fn into_inner(self) -> D {
+        self.distr
+    }
+}
+
+impl<D: Clone, H> Clone for IntoDistribution<D, H> {
+    fn clone(&self) -> Self {
+        Self {
+            distr: self.distr.clone(),
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<D: core::fmt::Debug, H> core::fmt::Debug for IntoDistribution<D, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("IntoDistribution")
+            .field(&self.distr)
+            .finish()
+    }
+}
+
+impl<T, D, H> Distribution<T> for IntoDistribution<D, H>
+where
+    D: PureDistribution<T>,
+    H: Hasher + Default + Clone,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let seed: u64 = rng.gen();
+
+        self.distr.sample(PureRandomGenerator::<H>::new(seed))
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    struct Pair;
+
+    impl PureDistribution<(u32, u32)> for Pair {
+        fn sample<H>(&self, rng: PureRandomGenerator<H>) -> (u32, u32)
+        where
+            H: Hasher + Default + Clone,
+        {
+            (rng.seed("a").gen(), rng.seed("b").gen())
+        }
+    }
+
+    #[test]
+    fn test_sample_pure() {
+        let rng = PureRng::new("root");
+        let pair = rng.sample_pure(Pair);
+
+        assert_eq!(pair, rng.sample_pure(&Pair));
+        assert_eq!(pair.0, rng.seed("a").gen::<u32>());
+        assert_eq!(pair.1, rng.seed("b").gen::<u32>());
+    }
+
+    #[test]
+    fn test_adapters() {
+        let rng = PureRng::new("root");
+
+        let direct: u64 = rng.gen();
+        let adapted: u64 = rng.sample_pure(FromDistribution(rand::distributions::Standard));
+        assert_eq!(direct, adapted);
+
+        let seeder = rng.seed("seeder");
+        let seed: u64 = seeder.gen();
+        let pair: (u32, u32) = seeder.sample(IntoDistribution::<
+            _,
+            crate::StableHasher<rapidhash::RapidHasher>,
+        >::new(Pair));
+        assert_eq!(pair, PureRng::new(seed).sample_pure(Pair));
+    }
+}
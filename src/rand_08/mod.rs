@@ -0,0 +1,214 @@
+//! Compatibility layer for `rand` 0.8, enabled by the `rand_08` feature.
+
+pub mod distributions;
+pub mod seq;
+
+use core::hash::Hasher;
+
+use rand::{
+    distributions::{
+        uniform::{SampleRange, SampleUniform},
+        Distribution, Standard,
+    },
+    Fill, Rng, RngCore, SeedableRng,
+};
+
+use crate::{Hasher128, PureRandomGenerator, PureRandomGenerator128, PureStream};
+
+impl<H> RngCore for PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn next_u32(&mut self) -> u32 {
+        // Good enough for government work.
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draw()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<H> RngCore for PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    fn next_u32(&mut self) -> u32 {
+        self.next_u128() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_u128() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.fill_bytes_via_u128(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl<H> SeedableRng for PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// The little-endian bytes of a `u64`.
+    type Seed = [u8; 8];
+
+    /// Equivalent to `PureRandomGenerator::new(u64::from_le_bytes(seed))`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    /// Equivalent to `PureRandomGenerator::new(state)`.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+impl<H> SeedableRng for PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    /// The little-endian bytes of a `u128`.
+    type Seed = [u8; 16];
+
+    /// Equivalent to `PureRandomGenerator128::new(u128::from_le_bytes(seed))`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u128::from_le_bytes(seed))
+    }
+
+    /// Equivalent to `PureRandomGenerator128::new(state)`.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+impl RngCore for PureStream {
+    fn next_u32(&mut self) -> u32 {
+        // The low bits of xoshiro256++ are slightly weaker than the high ones.
+        (self.next() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for PureStream {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::from_seed_bytes(seed)
+    }
+}
+
+/// Wrappers for the [`Rng`] trait functions.
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Return a random value supporting the [`Standard`] distribution.
+    ///
+    /// See [`Rng::gen`].
+    #[inline]
+    pub fn gen<T>(mut self) -> T
+    where
+        Standard: Distribution<T>,
+    {
+        Rng::gen(&mut self)
+    }
+
+    /// Generate a random value in the given range.
+    ///
+    /// See [`Rng::gen_range`].
+    pub fn gen_range<T, Q>(mut self, range: Q) -> T
+    where
+        T: SampleUniform,
+        Q: SampleRange<T>,
+    {
+        Rng::gen_range(&mut self, range)
+    }
+
+    /// Sample a new value, using the given distribution.
+    ///
+    /// See [`Rng::sample`].
+    pub fn sample<T, D: Distribution<T>>(mut self, distr: D) -> T {
+        Rng::sample(&mut self, distr)
+    }
+
+    /// Create an iterator that generates values using the given distribution.
+    ///
+    /// See [`Rng::sample_iter`].
+    pub fn sample_iter<T, D>(self, distr: D) -> rand::distributions::DistIter<D, Self, T>
+    where
+        D: Distribution<T>,
+        Self: Sized,
+    {
+        Rng::sample_iter(self, distr)
+    }
+
+    /// Fill any type implementing [`Fill`] with random data.
+    ///
+    /// See [`Rng::fill`].
+    pub fn fill<T: Fill + ?Sized>(mut self, dest: &mut T) {
+        Rng::fill(&mut self, dest)
+    }
+
+    /// Fill any type implementing [`Fill`] with random data
+    ///
+    /// See [`Rng::try_fill`].
+    pub fn try_fill<T: Fill + ?Sized>(mut self, dest: &mut T) -> Result<(), rand::Error> {
+        Rng::try_fill(&mut self, dest)
+    }
+
+    /// Converts the generator into a conventional stream generator, such as
+    /// [`PureStream`], seeded from the generator's state.
+    ///
+    /// The seed is filled with `fill_bytes`, so the same generator always
+    /// produces the same stream. Use this for bulk work, eg. shuffling a large
+    /// slice, which would otherwise hash once per value drawn.
+    pub fn into_stream<R: SeedableRng>(mut self) -> R {
+        let mut seed = R::Seed::default();
+        self.fill_bytes(seed.as_mut());
+
+        R::from_seed(seed)
+    }
+
+    /// Return a bool with a probability `p` of being true.
+    ///
+    /// See [`Rng::gen_bool`].
+    #[inline]
+    pub fn gen_bool(mut self, p: f64) -> bool {
+        Rng::gen_bool(&mut self, p)
+    }
+
+    /// Return a bool with a probability of `numerator/denominator` of being true.
+    ///
+    /// See [`Rng::gen_ratio`].
+    #[inline]
+    pub fn gen_ratio(mut self, numerator: u32, denominator: u32) -> bool {
+        Rng::gen_ratio(&mut self, numerator, denominator)
+    }
+}
@@ -1,15 +1,21 @@
 //! Wrappers for the [`rand::seq::index`] functions.
 
+use core::hash::Hasher;
+
+#[cfg(feature = "std")]
 use rand::distributions::WeightedError;
 use rand::seq::index::IndexVec;
 
-use crate::PureRng;
+use crate::PureRandomGenerator;
 
 /// Randomly sample exactly `amount` distinct indices from `0..length`, and
 /// return them in random order (fully shuffled).
 ///
 /// See [`rand::seq::index::sample`]
-pub fn sample(mut rng: PureRng, length: usize, amount: usize) -> IndexVec {
+pub fn sample<H>(mut rng: PureRandomGenerator<H>, length: usize, amount: usize) -> IndexVec
+where
+    H: Hasher + Default + Clone,
+{
     rand::seq::index::sample(&mut rng, length, amount)
 }
 
@@ -18,22 +24,25 @@ pub fn sample(mut rng: PureRng, length: usize, amount: usize) -> IndexVec {
 /// ordering).
 ///
 /// See [`rand::seq::index::sample_weighted`]
-pub fn sample_weighted<F, X>(
-    mut rng: PureRng,
+#[cfg(feature = "std")]
+pub fn sample_weighted<H, F, X>(
+    mut rng: PureRandomGenerator<H>,
     length: usize,
     weight: F,
     amount: usize,
 ) -> Result<IndexVec, WeightedError>
 where
+    H: Hasher + Default + Clone,
     F: Fn(usize) -> X,
     X: Into<f64>,
 {
     rand::seq::index::sample_weighted(&mut rng, length, weight, amount)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rapidhash"))]
 mod tests {
     use super::*;
+    use crate::PureRng;
 
     #[test]
     fn test_sample() {
@@ -50,6 +59,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn test_sample_weighted() {
         let rng = PureRng::default();
         let length = 10;
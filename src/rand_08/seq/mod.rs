@@ -1,13 +1,18 @@
+#[cfg(feature = "alloc")]
 pub mod index;
 
-use std::hash::Hasher;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::hash::Hasher;
 
+use rand::seq::{IteratorRandom, SliceRandom};
+#[cfg(feature = "alloc")]
 use rand::{
     distributions::{
         uniform::{SampleBorrow, SampleUniform},
         WeightedError,
     },
-    seq::{IteratorRandom, SliceChooseIter, SliceRandom},
+    seq::SliceChooseIter,
 };
 
 use crate::PureRandomGenerator;
@@ -46,6 +51,7 @@ where
     /// Collects `amount` values at random from the iterator into a vector.
     ///
     /// See [IteratorRandom::choose_multiple].
+    #[cfg(feature = "alloc")]
     fn choose_multiple(
         mut self,
         mut rng: PureRandomGenerator<H>,
@@ -85,16 +91,18 @@ where
     /// and in random order.
     ///
     /// See [SliceRandom::choose_multiple].
+    #[cfg(feature = "alloc")]
     fn choose_multiple(
         &self,
         rng: PureRandomGenerator<H>,
         amount: usize,
-    ) -> SliceChooseIter<Self, Self::Item>;
+    ) -> SliceChooseIter<'_, Self, Self::Item>;
 
     /// Similar to [`SlicePureRandom::choose`], but where the likelihood of each outcome may be
     /// specified.
     ///
     /// See [SliceRandom::choose_weighted].
+    #[cfg(feature = "alloc")]
     fn choose_weighted<F, B, X>(
         &self,
         rng: PureRandomGenerator<H>,
@@ -113,6 +121,7 @@ where
     /// be specified.
     ///
     /// See [SliceRandom::choose_weighted_mut].
+    #[cfg(feature = "alloc")]
     fn choose_weighted_mut<F, B, X>(
         &mut self,
         rng: PureRandomGenerator<H>,
@@ -131,12 +140,13 @@ where
     /// inclusion in the output may be specified.
     ///
     /// See [SliceRandom::choose_multiple_weighted].
+    #[cfg(feature = "std")]
     fn choose_multiple_weighted<F, X>(
         &self,
         rng: PureRandomGenerator<H>,
         amount: usize,
         weight: F,
-    ) -> Result<SliceChooseIter<Self, Self::Item>, WeightedError>
+    ) -> Result<SliceChooseIter<'_, Self, Self::Item>, WeightedError>
     where
         F: Fn(&Self::Item) -> X,
         X: Into<f64>;
@@ -170,14 +180,16 @@ where
         SliceRandom::choose_mut(self, &mut rng)
     }
 
+    #[cfg(feature = "alloc")]
     fn choose_multiple(
         &self,
         mut rng: PureRandomGenerator<H>,
         amount: usize,
-    ) -> SliceChooseIter<Self, Self::Item> {
+    ) -> SliceChooseIter<'_, Self, Self::Item> {
         SliceRandom::choose_multiple(self, &mut rng, amount)
     }
 
+    #[cfg(feature = "alloc")]
     fn choose_weighted<F, B, X>(
         &self,
         mut rng: PureRandomGenerator<H>,
@@ -195,6 +207,7 @@ where
         SliceRandom::choose_weighted(self, &mut rng, weight)
     }
 
+    #[cfg(feature = "alloc")]
     fn choose_weighted_mut<F, B, X>(
         &mut self,
         mut rng: PureRandomGenerator<H>,
@@ -212,12 +225,13 @@ where
         SliceRandom::choose_weighted_mut(self, &mut rng, weight)
     }
 
+    #[cfg(feature = "std")]
     fn choose_multiple_weighted<F, X>(
         &self,
         mut rng: PureRandomGenerator<H>,
         amount: usize,
         weight: F,
-    ) -> Result<SliceChooseIter<Self, Self::Item>, WeightedError>
+    ) -> Result<SliceChooseIter<'_, Self, Self::Item>, WeightedError>
     where
         F: Fn(&Self::Item) -> X,
         X: Into<f64>,
@@ -238,11 +252,11 @@ where
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rapidhash"))]
 mod tests {
-    use super::super::*;
     use super::IteratorPureRandom;
     use super::SlicePureRandom;
+    use crate::PureRng;
 
     #[test]
     fn test_iterator() {
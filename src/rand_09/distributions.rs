@@ -0,0 +1,138 @@
+//! Adapters between [`PureDistribution`] and the `rand` 0.9 [`Distribution`]
+//! trait.
+
+use core::{hash::Hasher, marker::PhantomData};
+
+use rand09::{distr::Distribution, Rng};
+
+use crate::{distributions::PureDistribution, PureRandomGenerator};
+
+/// Adapts any [`Distribution`] into a [`PureDistribution`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{rand_09::distributions::FromDistribution, PureRng};
+/// use rand09::distr::StandardUniform;
+///
+/// let value: u64 = PureRng::new(1234).sample_pure(FromDistribution(StandardUniform));
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct FromDistribution<D>(pub D);
+
+impl<T, D> PureDistribution<T> for FromDistribution<D>
+where
+    D: Distribution<T>,
+{
+    fn sample<H>(&self, mut rng: PureRandomGenerator<H>) -> T
+    where
+        H: Hasher + Default + Clone,
+    {
+        Rng::sample(&mut rng, &self.0)
+    }
+}
+
+/// Adapts a [`PureDistribution`] into a [`Distribution`], so that it can be
+/// used with any [`Rng`].
+///
+/// A single `u64` is drawn from the `Rng` and used to seed a fresh
+/// [`PureRandomGenerator<H>`], which is then passed to the wrapped
+/// distribution.
+pub struct IntoDistribution<D, H> {
+    distr: D,
+    hasher: PhantomData<fn() -> H>,
+}
+
+impl<D, H> IntoDistribution<D, H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Wraps the given [`PureDistribution`].
+    pub fn new(distr: D) -> Self {
+        Self {
+            distr,
+            hasher: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped [`PureDistribution`].
+    pub fn into_inner(self) -> D {
+        self.distr
+    }
+}
+
+impl<D: Clone, H> Clone for IntoDistribution<D, H> {
+    fn clone(&self) -> Self {
+        Self {
+            distr: self.distr.clone(),
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<D: core::fmt::Debug, H> core::fmt::Debug for IntoDistribution<D, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("IntoDistribution")
+            .field(&self.distr)
+            .finish()
+    }
+}
+
+impl<T, D, H> Distribution<T> for IntoDistribution<D, H>
+where
+    D: PureDistribution<T>,
+    H: Hasher + Default + Clone,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        let seed: u64 = rng.random();
+
+        self.distr.sample(PureRandomGenerator::<H>::new(seed))
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    struct Pair;
+
+    impl PureDistribution<(u32, u32)> for Pair {
+        fn sample<H>(&self, rng: PureRandomGenerator<H>) -> (u32, u32)
+        where
+            H: Hasher + Default + Clone,
+        {
+            (rng.seed("a").random(), rng.seed("b").random())
+        }
+    }
+
+    #[test]
+    fn test_sample_pure() {
+        let rng = PureRng::new("root");
+        let pair = rng.sample_pure(Pair);
+
+        assert_eq!(pair, rng.sample_pure(&Pair));
+        assert_eq!(pair.0, rng.seed("a").random::<u32>());
+        assert_eq!(pair.1, rng.seed("b").random::<u32>());
+    }
+
+    #[test]
+    fn test_adapters() {
+        let rng = PureRng::new("root");
+
+        let direct: u64 = rng.random();
+        let adapted: u64 = rng.sample_pure(FromDistribution(rand09::distr::StandardUniform));
+        assert_eq!(direct, adapted);
+
+        let mut seeder = rng.seed("seeder");
+        let seed: u64 = seeder.random();
+        let pair: (u32, u32) = rand09::Rng::sample(
+            &mut seeder,
+            IntoDistribution::<_, crate::StableHasher<rapidhash::RapidHasher>>::new(Pair),
+        );
+        assert_eq!(pair, PureRng::new(seed).sample_pure(Pair));
+    }
+}
@@ -0,0 +1,247 @@
+//! Compatibility layer for `rand` 0.9, enabled by the `rand_09` feature.
+//!
+//! The wrappers here follow the renamed 0.9 API, eg. `random` and
+//! `random_range` rather than `gen` and `gen_range`. Both this and the 0.8
+//! layer can be enabled at once to ease migration. In that case the
+//! [`sample`](PureRandomGenerator::sample), `sample_iter` and `fill` wrappers
+//! keep their 0.8 signatures; the 0.9 versions are still reachable through
+//! the [`Rng`] trait, which [`PureRandomGenerator`] implements for both
+//! versions.
+
+pub mod distributions;
+pub mod seq;
+
+use core::hash::Hasher;
+
+#[cfg(not(feature = "rand_08"))]
+use rand09::Fill;
+use rand09::{
+    distr::{
+        uniform::{SampleRange, SampleUniform},
+        Distribution, StandardUniform,
+    },
+    rand_core, Rng, RngCore, SeedableRng,
+};
+
+use crate::{Hasher128, PureRandomGenerator, PureRandomGenerator128, PureStream};
+
+impl<H> RngCore for PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    fn next_u32(&mut self) -> u32 {
+        // Good enough for government work.
+        self.draw() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draw()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest);
+    }
+}
+
+impl<H> RngCore for PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    fn next_u32(&mut self) -> u32 {
+        self.next_u128() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_u128() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.fill_bytes_via_u128(dest);
+    }
+}
+
+impl<H> SeedableRng for PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// The little-endian bytes of a `u64`.
+    type Seed = [u8; 8];
+
+    /// Equivalent to `PureRandomGenerator::new(u64::from_le_bytes(seed))`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u64::from_le_bytes(seed))
+    }
+
+    /// Equivalent to `PureRandomGenerator::new(state)`.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+impl<H> SeedableRng for PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    /// The little-endian bytes of a `u128`.
+    type Seed = [u8; 16];
+
+    /// Equivalent to `PureRandomGenerator128::new(u128::from_le_bytes(seed))`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(u128::from_le_bytes(seed))
+    }
+
+    /// Equivalent to `PureRandomGenerator128::new(state)`.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
+impl RngCore for PureStream {
+    fn next_u32(&mut self) -> u32 {
+        // The low bits of xoshiro256++ are slightly weaker than the high ones.
+        (self.next() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest);
+    }
+}
+
+impl SeedableRng for PureStream {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::from_seed_bytes(seed)
+    }
+}
+
+/// Wrappers for the `rand` 0.9 [`Rng`] trait functions.
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Return a random value via the [`StandardUniform`] distribution.
+    ///
+    /// See [`Rng::random`].
+    #[inline]
+    pub fn random<T>(mut self) -> T
+    where
+        StandardUniform: Distribution<T>,
+    {
+        Rng::random(&mut self)
+    }
+
+    /// Return an iterator over [`random`](Self::random) variates.
+    ///
+    /// See [`Rng::random_iter`].
+    pub fn random_iter<T>(self) -> rand09::distr::Iter<StandardUniform, Self, T>
+    where
+        StandardUniform: Distribution<T>,
+    {
+        Rng::random_iter(self)
+    }
+
+    /// Generate a random value in the given range.
+    ///
+    /// See [`Rng::random_range`].
+    pub fn random_range<T, Q>(mut self, range: Q) -> T
+    where
+        T: SampleUniform,
+        Q: SampleRange<T>,
+    {
+        Rng::random_range(&mut self, range)
+    }
+
+    /// Return a bool with a probability `p` of being true.
+    ///
+    /// See [`Rng::random_bool`].
+    #[inline]
+    pub fn random_bool(mut self, p: f64) -> bool {
+        Rng::random_bool(&mut self, p)
+    }
+
+    /// Return a bool with a probability of `numerator/denominator` of being true.
+    ///
+    /// See [`Rng::random_ratio`].
+    #[inline]
+    pub fn random_ratio(mut self, numerator: u32, denominator: u32) -> bool {
+        Rng::random_ratio(&mut self, numerator, denominator)
+    }
+}
+
+/// Wrappers for the `rand` 0.9 [`Rng`] trait functions whose names clash with
+/// the 0.8 ones. Only available when the `rand_08` feature is disabled.
+#[cfg(not(feature = "rand_08"))]
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Sample a new value, using the given distribution.
+    ///
+    /// See [`Rng::sample`].
+    pub fn sample<T, D: Distribution<T>>(mut self, distr: D) -> T {
+        Rng::sample(&mut self, distr)
+    }
+
+    /// Create an iterator that generates values using the given distribution.
+    ///
+    /// See [`Rng::sample_iter`].
+    pub fn sample_iter<T, D>(self, distr: D) -> rand09::distr::Iter<D, Self, T>
+    where
+        D: Distribution<T>,
+    {
+        Rng::sample_iter(self, distr)
+    }
+
+    /// Fill any type implementing [`Fill`] with random data.
+    ///
+    /// See [`Rng::fill`].
+    pub fn fill<T: Fill + ?Sized>(mut self, dest: &mut T) {
+        Rng::fill(&mut self, dest)
+    }
+
+    /// Converts the generator into a conventional stream generator, such as
+    /// [`PureStream`], seeded from the generator's state.
+    ///
+    /// See the `rand` 0.8 version. When both are enabled, `R::from_rng(&mut
+    /// rng)` seeds a 0.9 generator the same way.
+    pub fn into_stream<R: SeedableRng>(mut self) -> R {
+        let mut seed = R::Seed::default();
+        self.fill_bytes(seed.as_mut());
+
+        R::from_seed(seed)
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use crate::PureRng;
+
+    #[test]
+    fn test_random() {
+        let rng = PureRng::new("root");
+
+        let val_1: u64 = rng.seed("lol").random();
+        let val_2: u64 = rng.seed("lol").random();
+        assert_eq!(val_1, val_2);
+
+        let roll: u8 = rng.seed("roll").random_range(1..=6);
+        assert!((1..=6).contains(&roll));
+    }
+
+    #[test]
+    fn test_seedable_rng() {
+        use rand09::SeedableRng;
+
+        assert!(PureRng::seed_from_u64(1234) == PureRng::new(1234u64));
+        assert!(PureRng::from_seed(1234u64.to_le_bytes()) == PureRng::new(1234u64));
+
+        let mut source = PureRng::new("source");
+        let expected = PureRng::new(rand09::RngCore::next_u64(&mut source));
+        assert!(PureRng::from_rng(&mut PureRng::new("source")) == expected);
+    }
+}
@@ -0,0 +1,76 @@
+//! Wrappers for the [`rand09::seq::index`] functions.
+
+use core::hash::Hasher;
+
+use rand09::seq::index::IndexVec;
+#[cfg(feature = "std")]
+use rand09::seq::WeightError;
+
+use crate::PureRandomGenerator;
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`, and
+/// return them in random order (fully shuffled).
+///
+/// See [`rand09::seq::index::sample`]
+pub fn sample<H>(mut rng: PureRandomGenerator<H>, length: usize, amount: usize) -> IndexVec
+where
+    H: Hasher + Default + Clone,
+{
+    rand09::seq::index::sample(&mut rng, length, amount)
+}
+
+/// Randomly sample exactly `amount` distinct indices from `0..length`, and
+/// return them in an arbitrary order (there is no guarantee of shuffling or
+/// ordering).
+///
+/// See [`rand09::seq::index::sample_weighted`]
+#[cfg(feature = "std")]
+pub fn sample_weighted<H, F, X>(
+    mut rng: PureRandomGenerator<H>,
+    length: usize,
+    weight: F,
+    amount: usize,
+) -> Result<IndexVec, WeightError>
+where
+    H: Hasher + Default + Clone,
+    F: Fn(usize) -> X,
+    X: Into<f64>,
+{
+    rand09::seq::index::sample_weighted(&mut rng, length, weight, amount)
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    #[test]
+    fn test_sample() {
+        let rng = PureRng::default();
+        let length = 10;
+        let amount = 5;
+        let indices = sample(rng, length, amount);
+
+        assert_eq!(indices.len(), amount);
+
+        for i in indices.iter() {
+            assert!((0..length).contains(&i));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sample_weighted() {
+        let rng = PureRng::default();
+        let length = 10;
+        let amount = 5;
+        let weight = |i| i as f64;
+        let indices = sample_weighted(rng, length, weight, amount).unwrap();
+
+        assert_eq!(indices.len(), amount);
+
+        for i in indices.iter() {
+            assert!((0..length).contains(&i));
+        }
+    }
+}
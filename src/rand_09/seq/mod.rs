@@ -0,0 +1,261 @@
+#[cfg(feature = "alloc")]
+pub mod index;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use rand09::seq::{IndexedMutRandom, IndexedRandom, IteratorRandom, SliceRandom};
+#[cfg(feature = "alloc")]
+use rand09::{
+    distr::{
+        uniform::{SampleBorrow, SampleUniform},
+        weighted::Weight,
+    },
+    seq::{SliceChooseIter, WeightError},
+};
+
+use crate::PureRandomGenerator;
+
+/// Wrappers for the [IteratorRandom] trait functions. Implemented on all iterators.
+pub trait IteratorPureRandom<H>: Iterator + Sized
+where
+    H: Hasher + Default + Clone,
+{
+    /// Choose one element at random from the iterator.
+    ///
+    /// See [IteratorRandom::choose].
+    fn choose(self, mut rng: PureRandomGenerator<H>) -> Option<Self::Item> {
+        IteratorRandom::choose(self, &mut rng)
+    }
+
+    /// Choose one element at random from the iterator.
+    ///
+    /// See [IteratorRandom::choose_stable].
+    fn choose_stable(self, mut rng: PureRandomGenerator<H>) -> Option<Self::Item> {
+        IteratorRandom::choose_stable(self, &mut rng)
+    }
+
+    /// Collects values at random from the iterator into a supplied buffer
+    /// until that buffer is filled.
+    ///
+    /// See [IteratorRandom::choose_multiple_fill].
+    fn choose_multiple_fill(
+        self,
+        mut rng: PureRandomGenerator<H>,
+        buf: &mut [Self::Item],
+    ) -> usize {
+        IteratorRandom::choose_multiple_fill(self, &mut rng, buf)
+    }
+
+    /// Collects `amount` values at random from the iterator into a vector.
+    ///
+    /// See [IteratorRandom::choose_multiple].
+    #[cfg(feature = "alloc")]
+    fn choose_multiple(
+        mut self,
+        mut rng: PureRandomGenerator<H>,
+        amount: usize,
+    ) -> Vec<Self::Item> {
+        IteratorRandom::choose_multiple(&mut self, &mut rng, amount)
+    }
+}
+
+impl<I, H> IteratorPureRandom<H> for I
+where
+    I: Iterator + Sized,
+    H: Hasher + Default + Clone,
+{
+}
+
+/// Wrappers for the [IndexedRandom], [IndexedMutRandom] and [SliceRandom] trait
+/// functions. Implemented on all `[T]` slice types.
+pub trait SlicePureRandom<H>
+where
+    H: Hasher + Default + Clone,
+{
+    type Item;
+
+    /// Returns a reference to one random element of the slice, or `None` if the
+    /// slice is empty.
+    ///
+    /// See [IndexedRandom::choose].
+    fn choose(&self, rng: PureRandomGenerator<H>) -> Option<&Self::Item>;
+
+    /// Returns a mutable reference to one random element of the slice, or
+    /// `None` if the slice is empty.
+    ///
+    /// See [IndexedMutRandom::choose_mut].
+    fn choose_mut(&mut self, rng: PureRandomGenerator<H>) -> Option<&mut Self::Item>;
+
+    /// Chooses `amount` elements from the slice at random, without repetition,
+    /// and in random order.
+    ///
+    /// See [IndexedRandom::choose_multiple].
+    #[cfg(feature = "alloc")]
+    fn choose_multiple(
+        &self,
+        rng: PureRandomGenerator<H>,
+        amount: usize,
+    ) -> SliceChooseIter<'_, Self, Self::Item>;
+
+    /// Similar to [`SlicePureRandom::choose`], but where the likelihood of each outcome may be
+    /// specified.
+    ///
+    /// See [IndexedRandom::choose_weighted].
+    #[cfg(feature = "alloc")]
+    fn choose_weighted<F, B, X>(
+        &self,
+        rng: PureRandomGenerator<H>,
+        weight: F,
+    ) -> Result<&Self::Item, WeightError>
+    where
+        F: Fn(&Self::Item) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + Weight + PartialOrd<X>;
+
+    /// Similar to [`SlicePureRandom::choose_mut`], but where the likelihood of each outcome may
+    /// be specified.
+    ///
+    /// See [IndexedMutRandom::choose_weighted_mut].
+    #[cfg(feature = "alloc")]
+    fn choose_weighted_mut<F, B, X>(
+        &mut self,
+        rng: PureRandomGenerator<H>,
+        weight: F,
+    ) -> Result<&mut Self::Item, WeightError>
+    where
+        F: Fn(&Self::Item) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + Weight + PartialOrd<X>;
+
+    /// Similar to [`SlicePureRandom::choose_multiple`], but where the likelihood of each element's
+    /// inclusion in the output may be specified.
+    ///
+    /// See [IndexedRandom::choose_multiple_weighted].
+    #[cfg(feature = "std")]
+    fn choose_multiple_weighted<F, X>(
+        &self,
+        rng: PureRandomGenerator<H>,
+        amount: usize,
+        weight: F,
+    ) -> Result<SliceChooseIter<'_, Self, Self::Item>, WeightError>
+    where
+        F: Fn(&Self::Item) -> X,
+        X: Into<f64>;
+
+    /// Shuffle a mutable slice in place.
+    ///
+    /// See [SliceRandom::shuffle].
+    fn shuffle(&mut self, rng: PureRandomGenerator<H>);
+
+    /// Shuffle a slice in place, but exit early.
+    ///
+    /// See [SliceRandom::partial_shuffle].
+    fn partial_shuffle(
+        &mut self,
+        rng: PureRandomGenerator<H>,
+        amount: usize,
+    ) -> (&mut [Self::Item], &mut [Self::Item]);
+}
+
+impl<T, H> SlicePureRandom<H> for [T]
+where
+    H: Hasher + Default + Clone,
+{
+    type Item = T;
+
+    fn choose(&self, mut rng: PureRandomGenerator<H>) -> Option<&Self::Item> {
+        IndexedRandom::choose(self, &mut rng)
+    }
+
+    fn choose_mut(&mut self, mut rng: PureRandomGenerator<H>) -> Option<&mut Self::Item> {
+        IndexedMutRandom::choose_mut(self, &mut rng)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_multiple(
+        &self,
+        mut rng: PureRandomGenerator<H>,
+        amount: usize,
+    ) -> SliceChooseIter<'_, Self, Self::Item> {
+        IndexedRandom::choose_multiple(self, &mut rng, amount)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_weighted<F, B, X>(
+        &self,
+        mut rng: PureRandomGenerator<H>,
+        weight: F,
+    ) -> Result<&Self::Item, WeightError>
+    where
+        F: Fn(&Self::Item) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + Weight + PartialOrd<X>,
+    {
+        IndexedRandom::choose_weighted(self, &mut rng, weight)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn choose_weighted_mut<F, B, X>(
+        &mut self,
+        mut rng: PureRandomGenerator<H>,
+        weight: F,
+    ) -> Result<&mut Self::Item, WeightError>
+    where
+        F: Fn(&Self::Item) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + Weight + PartialOrd<X>,
+    {
+        IndexedMutRandom::choose_weighted_mut(self, &mut rng, weight)
+    }
+
+    #[cfg(feature = "std")]
+    fn choose_multiple_weighted<F, X>(
+        &self,
+        mut rng: PureRandomGenerator<H>,
+        amount: usize,
+        weight: F,
+    ) -> Result<SliceChooseIter<'_, Self, Self::Item>, WeightError>
+    where
+        F: Fn(&Self::Item) -> X,
+        X: Into<f64>,
+    {
+        IndexedRandom::choose_multiple_weighted(self, &mut rng, amount, weight)
+    }
+
+    fn shuffle(&mut self, mut rng: PureRandomGenerator<H>) {
+        SliceRandom::shuffle(self, &mut rng)
+    }
+
+    fn partial_shuffle(
+        &mut self,
+        mut rng: PureRandomGenerator<H>,
+        amount: usize,
+    ) -> (&mut [Self::Item], &mut [Self::Item]) {
+        SliceRandom::partial_shuffle(self, &mut rng, amount)
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::IteratorPureRandom;
+    use super::SlicePureRandom;
+    use crate::PureRng;
+
+    #[test]
+    fn test_iterator() {
+        let v = [1, 2, 3, 4, 5];
+        let chosen = v.iter().choose(PureRng::default()).unwrap();
+
+        assert!(v.contains(chosen));
+    }
+
+    #[test]
+    fn test_slice() {
+        let v = [1, 2, 3, 4, 5];
+        let chosen = &v.choose(PureRng::default()).unwrap();
+
+        assert!(v.contains(chosen));
+    }
+}
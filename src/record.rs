@@ -0,0 +1,525 @@
+//! Divergence recording and comparison, enabled by the `record` feature.
+//!
+//! A [`Recording`] hands out [`RecordingGenerator`]s, which behave like
+//! [`TracingGenerator`]s but log every value they generate along with the seed
+//! path that produced it. The resulting [`Log`] can be written to a file, and
+//! two logs from different runs or builds compared with
+//! [`Log::first_divergence`].
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+//! # {
+//! use pure_rng::record::{Recording, RecordingPureRng};
+//!
+//! fn generate(recording: &Recording) {
+//!     let rng: RecordingPureRng = recording.generator("world seed");
+//!     let _: u32 = rng.seed("monsters").seed(0).gen_range(0..10);
+//!     let _: bool = rng.seed("weather").gen();
+//! }
+//!
+//! let (a, b) = (Recording::new(), Recording::new());
+//! generate(&a);
+//! generate(&b);
+//!
+//! assert_eq!(a.log().first_divergence(&b.log()), None);
+//! # }
+//! ```
+//!
+//! # File format
+//!
+//! Logs are written as UTF-8 text, one record per line, after a header line.
+//! Each record is the seed path followed by a tab and the `Debug` form of the
+//! value. Backslashes, tabs, newlines and carriage returns are escaped.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Display, Formatter},
+    fs::File,
+    hash::{Hash, Hasher},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    trace::{SeedPath, TracingGenerator},
+    PureRandomGenerator, SeedLabel,
+};
+
+const HEADER: &str = "pure_rng record v1";
+
+/// A [`RecordingGenerator`] using the default hasher.
+#[cfg(feature = "rapidhash")]
+pub type RecordingPureRng = RecordingGenerator<crate::StableHasher<rapidhash::RapidHasher>>;
+
+/// A single generated value, and the seed path that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    pub path: String,
+    pub value: String,
+}
+
+/// A shared, growing log of generated values. Cloning a `Recording` gives
+/// another handle to the same log.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl Recording {
+    /// Creates a new, empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new root generator with the given seed, which logs to this
+    /// recording.
+    ///
+    /// See [`PureRandomGenerator::new`].
+    pub fn generator<H>(&self, hashable: impl Hash + Debug) -> RecordingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        RecordingGenerator {
+            rng: TracingGenerator::new(hashable),
+            recording: self.clone(),
+        }
+    }
+
+    /// Wraps an existing generator, which logs to this recording under the
+    /// given path.
+    pub fn wrap<H>(&self, rng: PureRandomGenerator<H>, path: SeedPath) -> RecordingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        RecordingGenerator {
+            rng: TracingGenerator::from_generator(rng, path),
+            recording: self.clone(),
+        }
+    }
+
+    /// Takes a snapshot of the records logged so far.
+    pub fn log(&self) -> Log {
+        Log {
+            records: self.records.lock().unwrap().clone(),
+        }
+    }
+
+    fn push(&self, path: &SeedPath, value: &impl Debug) {
+        let record = Record {
+            path: path.to_string(),
+            value: format!("{value:?}"),
+        };
+
+        self.records.lock().unwrap().push(record);
+    }
+}
+
+/// Wraps a [`TracingGenerator`], logging every generated value to a
+/// [`Recording`].
+///
+/// Generated values are identical to the untraced equivalents.
+#[derive(Debug, Clone)]
+pub struct RecordingGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: TracingGenerator<H>,
+    recording: Recording,
+}
+
+impl<H> RecordingGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// See [`TracingGenerator::seed`].
+    pub fn seed(&self, hashable: impl Hash + Debug) -> Self {
+        Self {
+            rng: self.rng.seed(hashable),
+            recording: self.recording.clone(),
+        }
+    }
+
+    /// See [`TracingGenerator::seed_named`].
+    pub fn seed_named(&self, hashable: impl Hash, name: impl Into<Arc<str>>) -> Self {
+        Self {
+            rng: self.rng.seed_named(hashable, name),
+            recording: self.recording.clone(),
+        }
+    }
+
+    /// See [`TracingGenerator::seed_label`].
+    pub fn seed_label(&self, label: SeedLabel) -> Self {
+        Self {
+            rng: self.rng.seed_label(label),
+            recording: self.recording.clone(),
+        }
+    }
+
+    /// Returns the path taken to reach this generator.
+    pub fn path(&self) -> &SeedPath {
+        self.rng.path()
+    }
+
+    /// Generates a value with the given function and logs it. This is the
+    /// escape hatch for any `rand` API without a dedicated wrapper here.
+    ///
+    /// See [`TracingGenerator::generate`].
+    pub fn generate<T: Debug>(self, f: impl FnOnce(&mut TracingGenerator<H>) -> T) -> T {
+        let traced = self.rng.generate(f);
+        self.recording.push(&traced.path, &traced.value);
+
+        traced.value
+    }
+}
+
+#[cfg(feature = "rand_08")]
+mod rand_08 {
+    use std::{fmt::Debug, hash::Hasher};
+
+    use rand::{
+        distributions::{
+            uniform::{SampleRange, SampleUniform},
+            Distribution, Standard,
+        },
+        Rng,
+    };
+
+    use super::RecordingGenerator;
+
+    /// Recording wrappers for the `rand` 0.8 [`Rng`] trait functions.
+    impl<H> RecordingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        /// See [`PureRandomGenerator::gen`](crate::PureRandomGenerator::gen).
+        pub fn gen<T: Debug>(self) -> T
+        where
+            Standard: Distribution<T>,
+        {
+            self.generate(|rng| Rng::gen(rng))
+        }
+
+        /// See [`PureRandomGenerator::gen_range`](crate::PureRandomGenerator::gen_range).
+        pub fn gen_range<T, Q>(self, range: Q) -> T
+        where
+            T: SampleUniform + Debug,
+            Q: SampleRange<T>,
+        {
+            self.generate(|rng| Rng::gen_range(rng, range))
+        }
+
+        /// See [`PureRandomGenerator::sample`](crate::PureRandomGenerator::sample).
+        pub fn sample<T: Debug, D: Distribution<T>>(self, distr: D) -> T {
+            self.generate(|rng| Rng::sample(rng, distr))
+        }
+    }
+}
+
+#[cfg(feature = "rand_09")]
+mod rand_09 {
+    use std::{fmt::Debug, hash::Hasher};
+
+    use rand09::{
+        distr::{
+            uniform::{SampleRange, SampleUniform},
+            Distribution, StandardUniform,
+        },
+        Rng,
+    };
+
+    use super::RecordingGenerator;
+
+    /// Recording wrappers for the `rand` 0.9 [`Rng`] trait functions.
+    impl<H> RecordingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        /// See [`PureRandomGenerator::random`](crate::PureRandomGenerator::random).
+        pub fn random<T: Debug>(self) -> T
+        where
+            StandardUniform: Distribution<T>,
+        {
+            self.generate(|rng| Rng::random(rng))
+        }
+
+        /// See [`PureRandomGenerator::random_range`](crate::PureRandomGenerator::random_range).
+        pub fn random_range<T, Q>(self, range: Q) -> T
+        where
+            T: SampleUniform + Debug,
+            Q: SampleRange<T>,
+        {
+            self.generate(|rng| Rng::random_range(rng, range))
+        }
+    }
+}
+
+/// A snapshot of the records logged to a [`Recording`], or read back from a
+/// file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Log {
+    pub records: Vec<Record>,
+}
+
+impl Log {
+    /// Writes the log in the text format described in the module docs.
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        writeln!(writer, "{HEADER}")?;
+
+        for record in &self.records {
+            writeln!(
+                writer,
+                "{}\t{}",
+                escape(&record.path),
+                escape(&record.value)
+            )?;
+        }
+
+        writer.flush()
+    }
+
+    /// Reads a log previously written with [`Log::write_to`].
+    pub fn read_from(reader: impl BufRead) -> io::Result<Self> {
+        let mut lines = reader.lines();
+
+        match lines.next().transpose()? {
+            Some(header) if header == HEADER => {}
+            _ => return Err(invalid_data("missing pure_rng record header")),
+        }
+
+        let records = lines
+            .map(|line| {
+                let line = line?;
+                let (path, value) = line
+                    .split_once('\t')
+                    .ok_or_else(|| invalid_data("malformed record"))?;
+
+                Ok(Record {
+                    path: unescape(path),
+                    value: unescape(value),
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self { records })
+    }
+
+    /// Writes the log to the given file, replacing it if it exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to(File::create(path)?)
+    }
+
+    /// Reads a log from the given file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read_from(BufReader::new(File::open(path)?))
+    }
+
+    /// Compares two logs, returning the first record at which they diverge.
+    ///
+    /// Records are matched by seed path, so that values generated in a
+    /// different order, eg. by another thread, are not reported. Where a path
+    /// generates several values, the n-th value on one side is matched with
+    /// the n-th on the other. Records are checked in the order they appear in
+    /// `self`, followed by any that only appear in `other`.
+    pub fn first_divergence(&self, other: &Log) -> Option<Divergence> {
+        let other_values = index(other);
+        let mut seen = HashMap::new();
+
+        for record in &self.records {
+            let occurrence = next_occurrence(&mut seen, &record.path);
+            let right = other_values.get(&(record.path.as_str(), occurrence));
+
+            if right != Some(&record.value.as_str()) {
+                return Some(Divergence {
+                    path: record.path.clone(),
+                    occurrence,
+                    left: Some(record.value.clone()),
+                    right: right.map(|value| value.to_string()),
+                });
+            }
+        }
+
+        let mut seen_other = HashMap::new();
+
+        for record in &other.records {
+            let occurrence = next_occurrence(&mut seen_other, &record.path);
+
+            if seen.get(record.path.as_str()).copied().unwrap_or(0) <= occurrence {
+                return Some(Divergence {
+                    path: record.path.clone(),
+                    occurrence,
+                    left: None,
+                    right: Some(record.value.clone()),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// The first point at which two [`Log`]s differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// The seed path of the divergent value.
+    pub path: String,
+    /// How many values were generated from this path before the divergent one.
+    pub occurrence: usize,
+    /// The value in the first log, or `None` if it is missing.
+    pub left: Option<String>,
+    /// The value in the second log, or `None` if it is missing.
+    pub right: Option<String>,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let missing = "<missing>".to_string();
+
+        write!(f, "{}", self.path)?;
+        if self.occurrence > 0 {
+            write!(f, " (value #{})", self.occurrence + 1)?;
+        }
+        write!(
+            f,
+            ": {} != {}",
+            self.left.as_ref().unwrap_or(&missing),
+            self.right.as_ref().unwrap_or(&missing)
+        )
+    }
+}
+
+fn next_occurrence<'a>(seen: &mut HashMap<&'a str, usize>, path: &'a str) -> usize {
+    let count = seen.entry(path).or_insert(0);
+    *count += 1;
+
+    *count - 1
+}
+
+fn index(log: &Log) -> HashMap<(&str, usize), &str> {
+    let mut seen = HashMap::new();
+
+    log.records
+        .iter()
+        .map(|record| {
+            let occurrence = next_occurrence(&mut seen, &record.path);
+            ((record.path.as_str(), occurrence), record.value.as_str())
+        })
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(all(test, feature = "rapidhash", feature = "rand_08"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    fn generate(recording: &Recording, extra: bool) {
+        let rng: RecordingPureRng = recording.generator("world");
+
+        let _: u32 = rng.seed("a").gen();
+        if extra {
+            let _: u32 = rng.seed("extra").gen();
+        }
+        let _: u32 = rng.seed("b").gen_range(0..10);
+        let _: u32 = rng.seed("b").gen_range(0..10);
+        let _: u32 = rng.seed_named("b", "c\tweird\nname").gen();
+        let _: u32 = rng.seed_named("d", "carriage return\r").gen();
+    }
+
+    #[test]
+    fn test_values_match_untraced() {
+        let recording = Recording::new();
+        let rng: RecordingPureRng = recording.generator("world");
+        let value: u64 = rng.seed("a").gen();
+
+        assert_eq!(value, PureRng::new("world").seed("a").gen::<u64>());
+        assert_eq!(recording.log().records[0].path, "world/a");
+        assert_eq!(recording.log().records[0].value, value.to_string());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let recording = Recording::new();
+        generate(&recording, false);
+
+        let mut bytes = Vec::new();
+        recording.log().write_to(&mut bytes).unwrap();
+
+        assert_eq!(Log::read_from(&bytes[..]).unwrap(), recording.log());
+        assert!(Log::read_from(&b"nonsense"[..]).is_err());
+
+        // `lines` would strip an unescaped carriage return from the end.
+        let log = Log {
+            records: vec![Record {
+                path: "world/line end\r".into(),
+                value: "\"\\r\"\r".into(),
+            }],
+        };
+        let mut bytes = Vec::new();
+        log.write_to(&mut bytes).unwrap();
+        assert_eq!(Log::read_from(&bytes[..]).unwrap(), log);
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let (a, b) = (Recording::new(), Recording::new());
+        generate(&a, false);
+        generate(&b, false);
+
+        assert_eq!(a.log().first_divergence(&b.log()), None);
+
+        let c = Recording::new();
+        generate(&c, true);
+
+        let divergence = a.log().first_divergence(&c.log()).unwrap();
+        assert_eq!(divergence.path, "world/extra");
+        assert_eq!(divergence.left, None);
+
+        let divergence = c.log().first_divergence(&a.log()).unwrap();
+        assert_eq!(divergence.path, "world/extra");
+        assert_eq!(divergence.right, None);
+
+        let mut log = a.log();
+        log.records[2].value = "nope".to_string();
+
+        let divergence = a.log().first_divergence(&log).unwrap();
+        assert_eq!(divergence.path, "world/b");
+        assert_eq!(divergence.occurrence, 1);
+        assert_eq!(divergence.right.as_deref(), Some("nope"));
+    }
+}
@@ -0,0 +1,601 @@
+//! Human-friendly world seed codes, enabled by the `seed_code` feature.
+//!
+//! A [`SeedCode`] is a 64-bit root seed which can be shown to players and
+//! typed back in. It's written either as a checksummed Crockford base32 code
+//! like `04HM-ASW9-NF6Y-ZNWB`, or as nine words like `axle gable idol lawn
+//! noble raven talon whisk tree`.
+//! Both forms ignore case and punctuation, and single-character typos are
+//! corrected when the checksum can tell which character was wrong.
+//!
+//! [`SeedCode::from_input`] accepts anything a player might type into a seed
+//! box, in the style of Minecraft: integers are used as the seed directly,
+//! codes and word lists are decoded, and any other text is hashed.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{seed_code::SeedCode, PureRng};
+//!
+//! let code = SeedCode::new(0x0123456789abcdef);
+//! assert_eq!(code.to_string(), "04HM-ASW9-NF6Y-ZNWB");
+//!
+//! // Typed back in by a friend, in lower case and with a typo.
+//! assert_eq!(SeedCode::from_input("04hm asw9 nf6t znwb"), code);
+//! assert_eq!(SeedCode::from_input("axle gable idol lawn noble ravn talon whisk tree"), code);
+//!
+//! let rng: PureRng = SeedCode::from_input("42").rng();
+//! assert!(rng == PureRng::new(42u64));
+//! # }
+//! ```
+
+mod words;
+
+use core::{
+    fmt::{self, Display, Formatter},
+    hash::Hasher,
+    str::FromStr,
+};
+
+use crate::{label::hash_label, PureRandomGenerator};
+use words::WORDS;
+
+/// The Crockford base32 alphabet.
+const SYMBOLS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The number of symbols in a code: 64 bits of seed and 16 of checksum.
+const CODE_LEN: usize = 16;
+
+/// The number of words in a word list: 8 for the seed and 1 for the checksum.
+const WORDS_LEN: usize = 9;
+
+/// The most combinations of corrected words to check against the checksum.
+/// Beyond this, the 8-bit checksum would often accept the wrong one.
+const MAX_WORD_CANDIDATES: usize = 16;
+
+/// A 64-bit root seed, which can be written as a code or list of words for
+/// players to share.
+///
+/// Hashing a `SeedCode` is the same as hashing its [`value`](Self::value), so
+/// `PureRng::new(code)` is the same generator as [`code.rng()`](Self::rng).
+///
+/// The encoded forms are covered by the value-stability policy described on
+/// [`STREAM_VERSION`](crate::STREAM_VERSION).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeedCode(u64);
+
+/// An error from decoding a [`SeedCode`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeedCodeError {
+    /// The code or word list had the wrong number of symbols or words.
+    InvalidLength,
+    /// The code contained a character outside the Crockford base32 alphabet.
+    InvalidCharacter,
+    /// The word list contained a word which isn't close to any in the list.
+    UnknownWord,
+    /// The checksum didn't match, and the mistake couldn't be corrected.
+    ChecksumMismatch,
+}
+
+impl Display for SeedCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "seed code has the wrong length"),
+            Self::InvalidCharacter => write!(f, "seed code contains an invalid character"),
+            Self::UnknownWord => write!(f, "seed code contains an unknown word"),
+            Self::ChecksumMismatch => write!(f, "seed code has a typo which can't be corrected"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SeedCodeError {}
+
+impl SeedCode {
+    /// Creates a code for the given seed.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the seed.
+    pub const fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Creates the generator for this seed, equivalent to
+    /// `PureRandomGenerator::new(self.value())`.
+    pub fn rng<H>(&self) -> PureRandomGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        PureRandomGenerator::new(self.0)
+    }
+
+    /// Interprets anything a player might type as a seed. This never fails.
+    ///
+    /// Surrounding whitespace is ignored, then the input is tried as:
+    ///
+    /// 1. An integer, as in Minecraft. Negative numbers are accepted, and
+    ///    `-1` is the same seed as `u64::MAX`.
+    /// 2. A code, as decoded by [`from_code`](Self::from_code). Typos are
+    ///    only corrected when the code is split into four groups of four, as
+    ///    [`Display`] writes it. Otherwise about one in 130 text seeds of 16
+    ///    letters and digits would be mistaken for a code with a typo.
+    /// 3. A word list, as decoded by [`from_words`](Self::from_words).
+    /// 4. Anything else is hashed, so eg. `"glacier"` always gives the same
+    ///    seed. This uses a fixed hash, independent of the generator's
+    ///    hasher, so it isn't the same as `PureRng::new("glacier")`.
+    pub fn from_input(input: &str) -> Self {
+        let input = input.trim();
+
+        if let Ok(value) = input.parse::<i64>() {
+            return Self(value as u64);
+        }
+        if let Ok(value) = input.parse::<u64>() {
+            return Self(value);
+        }
+
+        let code = match code_digits(input) {
+            Ok(digits) if is_grouped(input) => correct_digits(digits).ok(),
+            Ok(digits) => decode_digits(digits),
+            Err(_) => None,
+        };
+
+        code.or_else(|| Self::from_words(input).ok())
+            .unwrap_or_else(|| Self(hash_label(input.as_bytes())))
+    }
+
+    /// Decodes a code written by the [`Display`] implementation.
+    ///
+    /// Case, spaces and hyphens are ignored, and `I`, `L` and `O` are read as
+    /// `1`, `1` and `0`, following Crockford. If the checksum doesn't match,
+    /// every code one substituted character or swapped pair of neighbouring
+    /// characters away is tried, and the seed is accepted if exactly one
+    /// matches.
+    pub fn from_code(code: &str) -> Result<Self, SeedCodeError> {
+        correct_digits(code_digits(code)?)
+    }
+
+    /// Returns the code as a list of nine words, the last of which is a
+    /// checksum. Use `to_string()` on the result to get a `String`.
+    pub fn words(&self) -> Words {
+        Words(*self)
+    }
+
+    /// Decodes a list of words written by [`words`](Self::words).
+    ///
+    /// The words can be in any case, separated by any non-letters. A word
+    /// which isn't in the list is corrected if its first three letters match a
+    /// word, or failing that, if it's one typo away from some words. When
+    /// there's a choice of corrections, the seed is accepted if exactly one
+    /// combination of them matches the checksum.
+    pub fn from_words(words: &str) -> Result<Self, SeedCodeError> {
+        let mut candidates = [[0u8; MAX_WORD_CANDIDATES]; WORDS_LEN];
+        let mut counts = [0; WORDS_LEN];
+        let mut len = 0;
+
+        for word in words
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .filter(|word| !word.is_empty())
+        {
+            if len == WORDS_LEN {
+                return Err(SeedCodeError::InvalidLength);
+            }
+
+            counts[len] = word_candidates(word, &mut candidates[len])?;
+            len += 1;
+        }
+        if len != WORDS_LEN {
+            return Err(SeedCodeError::InvalidLength);
+        }
+
+        let combinations = counts
+            .iter()
+            .try_fold(1usize, |total, &count| total.checked_mul(count))
+            .filter(|&total| total <= MAX_WORD_CANDIDATES)
+            .ok_or(SeedCodeError::ChecksumMismatch)?;
+
+        let mut found = None;
+        for combination in 0..combinations {
+            // Pick one candidate for each word, counting in mixed radix.
+            let mut bytes = [0; WORDS_LEN];
+            let mut rest = combination;
+            for i in 0..WORDS_LEN {
+                bytes[i] = candidates[i][rest % counts[i]];
+                rest /= counts[i];
+            }
+
+            let value = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+            if word_checksum(value) == bytes[8] {
+                if found.is_some_and(|found| found != value) {
+                    return Err(SeedCodeError::ChecksumMismatch);
+                }
+                found = Some(value);
+            }
+        }
+
+        found.map(Self).ok_or(SeedCodeError::ChecksumMismatch)
+    }
+}
+
+impl Display for SeedCode {
+    /// Writes the code in four hyphenated groups of four symbols.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let bits = (self.0 as u128) << 16 | checksum(self.0) as u128;
+
+        for i in 0..CODE_LEN {
+            if i > 0 && i % 4 == 0 {
+                write!(f, "-")?;
+            }
+
+            let digit = (bits >> (5 * (CODE_LEN - 1 - i))) & 0x1f;
+            write!(f, "{}", SYMBOLS[digit as usize] as char)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SeedCode {
+    type Err = SeedCodeError;
+
+    /// Decodes a code with [`from_code`](Self::from_code).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_code(s)
+    }
+}
+
+impl From<u64> for SeedCode {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<SeedCode> for u64 {
+    fn from(code: SeedCode) -> Self {
+        code.0
+    }
+}
+
+/// A [`SeedCode`] written as words. See [`SeedCode::words`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Words(SeedCode);
+
+impl Display for Words {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = self.0 .0;
+
+        for byte in value.to_be_bytes() {
+            write!(f, "{} ", WORDS[byte as usize])?;
+        }
+
+        write!(f, "{}", WORDS[word_checksum(value) as usize])
+    }
+}
+
+/// The 16-bit checksum of a seed.
+fn checksum(value: u64) -> u16 {
+    (hash_label(&value.to_le_bytes()) >> 48) as u16
+}
+
+/// The 8-bit checksum of a seed, used by word lists.
+fn word_checksum(value: u64) -> u8 {
+    (checksum(value) >> 8) as u8
+}
+
+fn symbol_value(c: u8) -> Result<u8, SeedCodeError> {
+    match c.to_ascii_uppercase() {
+        b'O' => Ok(0),
+        b'I' | b'L' => Ok(1),
+        c => SYMBOLS
+            .iter()
+            .position(|&symbol| symbol == c)
+            .map(|value| value as u8)
+            .ok_or(SeedCodeError::InvalidCharacter),
+    }
+}
+
+/// Reads the symbol values of a code, ignoring case, spaces and hyphens.
+fn code_digits(code: &str) -> Result<[u8; CODE_LEN], SeedCodeError> {
+    let mut digits = [0; CODE_LEN];
+    let mut len = 0;
+    for c in code.bytes() {
+        if c == b'-' || c.is_ascii_whitespace() {
+            continue;
+        }
+        if len == CODE_LEN {
+            return Err(SeedCodeError::InvalidLength);
+        }
+
+        digits[len] = symbol_value(c)?;
+        len += 1;
+    }
+    if len != CODE_LEN {
+        return Err(SeedCodeError::InvalidLength);
+    }
+
+    Ok(digits)
+}
+
+/// Whether a code is split into four groups of four symbols.
+fn is_grouped(code: &str) -> bool {
+    let mut groups = code
+        .split(|c: char| c == '-' || c.is_ascii_whitespace())
+        .filter(|group| !group.is_empty());
+
+    groups.clone().count() == 4 && groups.all(|group| group.len() == 4)
+}
+
+/// Decodes the symbol values of a code, correcting a single typo if exactly
+/// one correction matches the checksum.
+fn correct_digits(digits: [u8; CODE_LEN]) -> Result<SeedCode, SeedCodeError> {
+    if let Some(code) = decode_digits(digits) {
+        return Ok(code);
+    }
+
+    // Look for a unique correction.
+    let mut found = None;
+    let mut consider = |digits| {
+        if let Some(code) = decode_digits(digits) {
+            if found.is_some_and(|found| found != code) {
+                return Err(SeedCodeError::ChecksumMismatch);
+            }
+            found = Some(code);
+        }
+        Ok(())
+    };
+
+    for i in 0..CODE_LEN {
+        for digit in 0..32 {
+            if digit != digits[i] {
+                let mut candidate = digits;
+                candidate[i] = digit;
+                consider(candidate)?;
+            }
+        }
+
+        if i + 1 < CODE_LEN && digits[i] != digits[i + 1] {
+            let mut candidate = digits;
+            candidate.swap(i, i + 1);
+            consider(candidate)?;
+        }
+    }
+
+    found.ok_or(SeedCodeError::ChecksumMismatch)
+}
+
+/// Decodes the symbol values of a code, returning `None` if the checksum
+/// doesn't match.
+fn decode_digits(digits: [u8; CODE_LEN]) -> Option<SeedCode> {
+    let bits = digits
+        .iter()
+        .fold(0u128, |bits, &digit| bits << 5 | digit as u128);
+    let value = (bits >> 16) as u64;
+
+    (checksum(value) == bits as u16).then_some(SeedCode(value))
+}
+
+/// Finds the words the given one could be, writing their indices into
+/// `candidates` and returning how many there are.
+fn word_candidates(
+    word: &str,
+    candidates: &mut [u8; MAX_WORD_CANDIDATES],
+) -> Result<usize, SeedCodeError> {
+    let matches = |f: &dyn Fn(&[u8]) -> bool, candidates: &mut [u8; MAX_WORD_CANDIDATES]| {
+        let mut count = 0;
+        for (index, known) in WORDS.iter().enumerate() {
+            if f(known.as_bytes()) {
+                if count < MAX_WORD_CANDIDATES {
+                    candidates[count] = index as u8;
+                }
+                count += 1;
+            }
+        }
+        count
+    };
+
+    let word = word.as_bytes();
+    let same = |a: &[u8], b: &[u8]| a.eq_ignore_ascii_case(b);
+
+    let count = matches(&|known| same(known, word), candidates);
+    if count > 0 {
+        return Ok(count);
+    }
+
+    if word.len() >= 3 {
+        let count = matches(&|known| same(&known[..3], &word[..3]), candidates);
+        if count > 0 {
+            return Ok(count);
+        }
+    }
+
+    match matches(&|known| one_edit_apart(known, word), candidates) {
+        0 => Err(SeedCodeError::UnknownWord),
+        count => Ok(count.min(MAX_WORD_CANDIDATES + 1)),
+    }
+}
+
+/// Whether the two words differ by a single substituted, inserted or deleted
+/// letter, ignoring case.
+fn one_edit_apart(a: &[u8], b: &[u8]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    let prefix = short
+        .iter()
+        .zip(long)
+        .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+        .count();
+    let skip = if short.len() == long.len() { 1 } else { 0 };
+
+    short.len() > prefix && short[prefix + skip..].eq_ignore_ascii_case(&long[prefix + 1..])
+        || short.len() == prefix && long.len() == prefix + 1
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    #[test]
+    fn test_code_roundtrip() {
+        for value in [0, 1, 1234, u64::MAX, 0x0123456789abcdef] {
+            let code = SeedCode::new(value);
+            let text = code.to_string();
+
+            assert_eq!(text.len(), 19);
+            assert_eq!(text.parse(), Ok(code));
+            assert_eq!(SeedCode::from_code(&text.to_lowercase()), Ok(code));
+            assert_eq!(SeedCode::from_code(&text.replace('-', " ")), Ok(code));
+        }
+    }
+
+    #[test]
+    fn test_code_crockford() {
+        let code = SeedCode::new(0);
+        let text = code.to_string();
+
+        // The checksum of zero contains a 1.
+        assert!(text.contains('1'));
+        assert_eq!(SeedCode::from_code(&text.replace('0', "o")), Ok(code));
+        assert_eq!(SeedCode::from_code(&text.replace('1', "l")), Ok(code));
+        assert_eq!(SeedCode::from_code(&text.replace('1', "I")), Ok(code));
+        assert_eq!(
+            SeedCode::from_code(&text.replace('0', "U")),
+            Err(SeedCodeError::InvalidCharacter)
+        );
+        assert_eq!(
+            SeedCode::from_code(&text[1..]),
+            Err(SeedCodeError::InvalidLength)
+        );
+        assert_eq!(
+            SeedCode::from_code(&(text.clone() + "0")),
+            Err(SeedCodeError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_code_typos() {
+        let rng = PureRng::new("typos");
+        let mut corrected = 0;
+
+        for i in 0..200u64 {
+            let code = SeedCode::new(rng.seed(i).draw());
+            let mut symbols: Vec<u8> = code.to_string().replace('-', "").into_bytes();
+            let position = (i % 16) as usize;
+
+            if i % 2 == 0 {
+                symbols[position] = SYMBOLS
+                    [(symbol_value(symbols[position]).unwrap() as usize + 1 + i as usize) % 32];
+            } else {
+                symbols.swap(position.min(14), position.min(14) + 1);
+            }
+
+            // A typo must never be accepted as a different seed.
+            match SeedCode::from_code(core::str::from_utf8(&symbols).unwrap()) {
+                Ok(decoded) => {
+                    assert_eq!(decoded, code);
+                    corrected += 1;
+                }
+                Err(error) => assert_eq!(error, SeedCodeError::ChecksumMismatch),
+            }
+        }
+
+        assert!(corrected > 150, "only corrected {corrected} of 200 typos");
+    }
+
+    #[test]
+    fn test_words_roundtrip() {
+        for value in [0, 1, 1234, u64::MAX, 0x0123456789abcdef] {
+            let code = SeedCode::new(value);
+            let words = code.words().to_string();
+
+            assert_eq!(words.split(' ').count(), 9);
+            assert_eq!(SeedCode::from_words(&words), Ok(code));
+            assert_eq!(SeedCode::from_words(&words.to_uppercase()), Ok(code));
+            assert_eq!(SeedCode::from_words(&words.replace(' ', ", ")), Ok(code));
+        }
+    }
+
+    #[test]
+    fn test_words_typos() {
+        let code = SeedCode::new(0x0123456789abcdef);
+        let words = code.words().to_string();
+        let mut list: Vec<String> = words.split(' ').map(String::from).collect();
+
+        // Misspelling the end of a word is corrected by its prefix.
+        list[0].push_str("zz");
+        assert_eq!(SeedCode::from_words(&list.join(" ")), Ok(code));
+
+        assert_eq!(
+            SeedCode::from_words("xyzzy plugh"),
+            Err(SeedCodeError::UnknownWord)
+        );
+        assert_eq!(
+            SeedCode::from_words(&list[..8].join(" ")),
+            Err(SeedCodeError::InvalidLength)
+        );
+
+        list.swap(1, 2);
+        assert!(SeedCode::from_words(&list.join(" ")).is_err());
+    }
+
+    #[test]
+    fn test_one_edit_apart() {
+        assert!(one_edit_apart(b"wolf", b"wolk"));
+        assert!(one_edit_apart(b"wolf", b"wol"));
+        assert!(one_edit_apart(b"wolf", b"wolfs"));
+        assert!(one_edit_apart(b"wolf", b"olf"));
+        assert!(one_edit_apart(b"wolf", b"WOLK"));
+        assert!(!one_edit_apart(b"wolf", b"wlof"));
+        assert!(!one_edit_apart(b"wolf", b"wo"));
+    }
+
+    #[test]
+    fn test_from_input() {
+        assert_eq!(SeedCode::from_input(" 1234 "), SeedCode::new(1234));
+        assert_eq!(SeedCode::from_input("-1"), SeedCode::new(u64::MAX));
+        assert_eq!(
+            SeedCode::from_input("18446744073709551615"),
+            SeedCode::new(u64::MAX)
+        );
+
+        let code = SeedCode::new(0xdeadbeef);
+        assert_eq!(SeedCode::from_input(&code.to_string()), code);
+        assert_eq!(SeedCode::from_input(&code.words().to_string()), code);
+
+        // Typos are only corrected in codes written in groups.
+        let typo = code.to_string().replacen('0', "1", 1);
+        assert_eq!(SeedCode::from_input(&typo), code);
+        assert_eq!(SeedCode::from_input(&typo.replace('-', " ")), code);
+        let typo = typo.replace('-', "");
+        assert_eq!(
+            SeedCode::from_input(&typo),
+            SeedCode::new(hash_label(typo.as_bytes()))
+        );
+
+        // So text seeds which happen to be in the code alphabet are hashed.
+        let rng = PureRng::new("text seeds");
+        for i in 0..1000 {
+            let text: String = (0..16)
+                .map(|j| SYMBOLS[rng.seed((i, j)).draw_below(32) as usize] as char)
+                .collect();
+            assert_eq!(
+                SeedCode::from_input(&text),
+                SeedCode::new(hash_label(text.as_bytes()))
+            );
+        }
+
+        let text = SeedCode::from_input("glacier");
+        assert_eq!(text, SeedCode::from_input("glacier"));
+        assert_ne!(text, SeedCode::from_input("Glacier"));
+
+        let rng: PureRng = SeedCode::from_input("42").rng();
+        assert!(rng == PureRng::new(42u64));
+        assert!(PureRng::new(code) == code.rng());
+    }
+}
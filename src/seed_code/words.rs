@@ -0,0 +1,28 @@
+/// The words used by [`SeedCode::words`](super::SeedCode::words), one for
+/// each byte value. Each has a unique first three letters, so that typos after
+/// those can be corrected.
+pub(super) const WORDS: [&str; 256] = [
+    "arch", "axle", "bell", "boot", "cake", "clam", "crab", "cube", "dawn", "deer", "dial", "dock",
+    "door", "dove", "drum", "duck", "dune", "dusk", "echo", "eel", "egg", "elk", "epic", "era",
+    "ewe", "fan", "farm", "fern", "fig", "fire", "flag", "foam", "fog", "fox", "frog", "gable",
+    "gate", "gear", "gecko", "gem", "ghost", "giant", "globe", "goat", "gold", "goose", "gorge",
+    "grain", "gull", "gust", "hail", "hat", "haven", "hawk", "hazel", "heart", "hedge", "hemp",
+    "herb", "hill", "hinge", "hive", "holly", "honey", "hood", "hound", "hull", "hymn", "ice",
+    "idol", "igloo", "inch", "index", "ink", "inn", "iris", "iron", "ivory", "ivy", "jade", "jam",
+    "jar", "jazz", "jelly", "jetty", "jewel", "judge", "juice", "kayak", "kelp", "key", "kiln",
+    "king", "kiosk", "kite", "kiwi", "knot", "koala", "lake", "lamb", "lance", "larch", "lava",
+    "lawn", "leaf", "ledge", "lemon", "lens", "lily", "lime", "linen", "lion", "llama", "lodge",
+    "lotus", "lunar", "lute", "lynx", "maize", "mango", "maple", "mask", "melon", "mill", "mint",
+    "mist", "moat", "monk", "moon", "moss", "moth", "mound", "mural", "nail", "nest", "net",
+    "night", "noble", "north", "nylon", "oak", "oasis", "oboe", "ocean", "olive", "onion", "onyx",
+    "opal", "orbit", "organ", "otter", "owl", "oxen", "panda", "paper", "pasta", "peach", "piano",
+    "pine", "plum", "polar", "pond", "poppy", "prism", "quail", "queen", "quill", "radar", "raft",
+    "rain", "ranch", "raven", "reed", "relic", "rice", "ridge", "river", "robin", "rose", "rugby",
+    "rune", "rust", "sage", "sand", "satin", "scarf", "seal", "shell", "siren", "sled", "sloth",
+    "smoke", "snail", "snow", "sofa", "solar", "spice", "spoon", "squid", "stone", "sugar", "sun",
+    "swan", "sword", "table", "talon", "tango", "tapir", "tent", "tiger", "toast", "topaz",
+    "torch", "tower", "trail", "tree", "tulip", "tusk", "twig", "umbra", "uncle", "urn", "vapor",
+    "vase", "venus", "verse", "vial", "vine", "viper", "visor", "wafer", "wagon", "wand", "wasp",
+    "water", "wave", "wax", "whale", "wheat", "whisk", "wind", "wolf", "wood", "wren", "yacht",
+    "yak", "yam", "yarn", "yeti", "yew", "yodel", "yolk", "zebra", "zero", "zinc", "zone",
+];
@@ -0,0 +1,255 @@
+//! Point sets over infinite worlds, enabled by the `spatial` feature.
+//!
+//! Placing trees or ore in a chunked world needs points which don't depend on
+//! which chunks were generated first. Each sampler here divides space into a
+//! grid of cells, and draws the candidate points in each cell from the
+//! generator seeded with the cell's integer coordinates, ie.
+//! `rng.seed((x, y))` in 2D. The points in any region are then computed from
+//! the cells around it alone, so querying chunks in any order, or a whole
+//! area at once, gives exactly the same points.
+//!
+//! - [`JitteredGrid`] places one point in every cell, at a random offset from
+//!   its centre. It's the cheapest, but the points are visibly aligned.
+//! - [`PoissonDisk`] places points at random, but never closer together than
+//!   a radius, giving an even, natural-looking blue noise distribution.
+//! - [`BlueNoise`] is like [`PoissonDisk`], but with the radius varying over
+//!   space, so the density can follow a biome or noise map.
+//!
+//! They all implement [`PointSampler`] in any number of dimensions, usually 2
+//! or 3. The cell coordinates are `i64`s, so boxes beyond that many cells from
+//! the origin hold no points. Points of different dimensions are independent.
+//! Samplers sharing a generator share candidates, so seed each one
+//! differently.
+//!
+//! Only basic floating point arithmetic is used, so the points are identical
+//! on every platform.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "rapidhash")]
+//! # {
+//! use pure_rng::{
+//!     spatial::{PointSampler, PoissonDisk},
+//!     PureRng,
+//! };
+//!
+//! let trees = PoissonDisk::new(PureRng::new("world").seed("trees"), 4.);
+//!
+//! // Chunks can be generated in any order.
+//! let east = trees.points([16., 0.], [32., 16.]);
+//! let west = trees.points([0., 0.], [16., 16.]);
+//!
+//! let mut both = trees.points([0., 0.], [32., 16.]);
+//! let mut chunks = [west, east].concat();
+//! both.sort_by(|a, b| a.partial_cmp(b).unwrap());
+//! chunks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+//! assert_eq!(both, chunks);
+//! # }
+//! ```
+
+mod poisson;
+
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+pub use poisson::{BlueNoise, PoissonDisk};
+
+use crate::{floor, PureRandomGenerator};
+
+/// An `N`-dimensional sampler of points.
+pub trait PointSampler<const N: usize> {
+    /// Returns the points inside the box from `min` inclusive to `max`
+    /// exclusive.
+    ///
+    /// The points are a pure function of the sampler, so the points of
+    /// adjacent boxes fit together seamlessly. Their order is unspecified.
+    fn points(&self, min: [f64; N], max: [f64; N]) -> Vec<[f64; N]>;
+}
+
+/// One point in every cell of a grid, jittered from the cell's centre.
+///
+/// The point of the cell with integer coordinates `(x, y, ...)` is drawn from
+/// `rng.seed((x, y, ...))`, with the coordinates as `i64`s.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct JitteredGrid<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+    /// The size of each cell.
+    pub spacing: f64,
+    /// How far each point can move from its cell's centre, as a fraction of
+    /// the cell. 0 gives a regular grid, and 1 lets a point fall anywhere in
+    /// its cell. Defaults to 1. Other values are clamped to `[0, 1]`, so that
+    /// points never leave their cells.
+    pub jitter: f64,
+}
+
+impl<H> JitteredGrid<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a grid with the given spacing, fully jittered.
+    pub fn new(rng: PureRandomGenerator<H>, spacing: f64) -> Self {
+        Self {
+            rng,
+            spacing,
+            jitter: 1.,
+        }
+    }
+
+    /// Returns the point of the given cell.
+    pub fn point<const N: usize>(&self, cell: [i64; N]) -> [f64; N] {
+        let mut rng = self.rng.seed_lattice(cell);
+        // Unlike `clamp`, this also replaces NaN.
+        let jitter = if self.jitter > 0. {
+            self.jitter.min(1.)
+        } else {
+            0.
+        };
+
+        cell.map(|x| (x as f64 + 0.5 + jitter * (rng.draw_unit() - 0.5)) * self.spacing)
+    }
+}
+
+impl<H, const N: usize> PointSampler<N> for JitteredGrid<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// # Panics
+    ///
+    /// Panics if the spacing isn't positive.
+    fn points(&self, min: [f64; N], max: [f64; N]) -> Vec<[f64; N]> {
+        assert!(self.spacing > 0., "the spacing must be positive");
+        let mut points = Vec::new();
+
+        for_each_cell(cells(min, max, self.spacing), |cell| {
+            let point = self.point(cell);
+            if contains(min, max, point) {
+                points.push(point);
+            }
+        });
+
+        points
+    }
+}
+
+/// The inclusive range of cells of the given size overlapping a box.
+fn cells<const N: usize>(min: [f64; N], max: [f64; N], size: f64) -> ([i64; N], [i64; N]) {
+    (min.map(|x| floor(x / size)), max.map(|x| floor(x / size)))
+}
+
+/// Calls `f` with every cell in the inclusive range.
+fn for_each_cell<const N: usize>((low, high): ([i64; N], [i64; N]), mut f: impl FnMut([i64; N])) {
+    if N == 0 || low.iter().zip(&high).any(|(low, high)| low > high) {
+        return;
+    }
+
+    let mut cell = low;
+    loop {
+        f(cell);
+
+        // Count through the cells like an odometer.
+        let mut axis = 0;
+        while cell[axis] == high[axis] {
+            cell[axis] = low[axis];
+            axis += 1;
+            if axis == N {
+                return;
+            }
+        }
+        cell[axis] += 1;
+    }
+}
+
+/// Whether the box from `min` inclusive to `max` exclusive contains a point.
+fn contains<const N: usize>(min: [f64; N], max: [f64; N], point: [f64; N]) -> bool {
+    (0..N).all(|axis| min[axis] <= point[axis] && point[axis] < max[axis])
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    /// Checks that the points of a region are the union of the points of its
+    /// quarters.
+    pub(super) fn check_seamless<const N: usize>(sampler: &impl PointSampler<N>, size: f64) {
+        let sorted = |mut points: Vec<[f64; N]>| {
+            points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points
+        };
+
+        let min = [-size / 2.; N];
+        let whole = sorted(sampler.points(min, [size / 2.; N]));
+
+        let mut parts = Vec::new();
+        for part in (0..1 << N).rev() {
+            let mut low = min;
+            let mut high = min;
+            for axis in 0..N {
+                let half = (part >> axis) & 1;
+                low[axis] += size / 2. * half as f64;
+                high[axis] = low[axis] + size / 2.;
+            }
+            parts.extend(sampler.points(low, high));
+        }
+
+        assert!(!whole.is_empty());
+        assert_eq!(whole, sorted(parts));
+    }
+
+    #[test]
+    fn test_for_each_cell() {
+        let mut cells = Vec::new();
+        for_each_cell(([-1, 0], [0, 2]), |cell| cells.push(cell));
+
+        assert_eq!(cells, [[-1, 0], [0, 0], [-1, 1], [0, 1], [-1, 2], [0, 2]]);
+
+        for_each_cell(([1], [0]), |_| panic!());
+    }
+
+    #[test]
+    fn test_jittered_grid() {
+        let grid = JitteredGrid::new(PureRng::new("grid"), 2.5);
+
+        let points = grid.points([-10., -10.], [10., 10.]);
+        assert_eq!(points.len(), 64);
+        for point in &points {
+            let cell = cells(*point, *point, 2.5).0;
+            assert_eq!(grid.point(cell), *point);
+        }
+
+        check_seamless::<2>(&grid, 20.);
+        check_seamless::<3>(&grid, 20.);
+
+        let regular = JitteredGrid { jitter: 0., ..grid };
+        assert_eq!(regular.point([1, -2]), [3.75, -3.75]);
+
+        // Too much jitter would move points out of their cells.
+        let wild = JitteredGrid { jitter: 5., ..grid };
+        assert_eq!(wild.point([3, 4]), grid.point([3, 4]));
+        check_seamless::<2>(&wild, 20.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_jittered_grid_spacing() {
+        JitteredGrid::new(PureRng::new("grid"), 0.).points([-1., -1.], [1., 1.]);
+    }
+
+    #[test]
+    fn test_far_away() {
+        let rng = PureRng::new("far");
+        let grid = JitteredGrid::new(rng, 1.);
+        let poisson = PoissonDisk::new(rng, 1.);
+
+        // Beyond the range of `i64` cells there are no points, but no panics.
+        for x in [-1e30, 1e30] {
+            let (min, max) = ([x, 0.], [x + 1., 1.]);
+            assert!(grid.points(min, max).is_empty());
+            assert!(poisson.points(min, max).is_empty());
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! Poisson disk sampling, decided one candidate at a time.
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+use core::hash::Hasher;
+
+use super::{cells, contains, for_each_cell, PointSampler};
+use crate::PureRandomGenerator;
+
+/// The default number of candidates in each cell.
+const CANDIDATES: u32 = 4;
+
+/// Random points, never closer together than a radius.
+///
+/// Space is divided into cells half the radius across, each holding a fixed
+/// number of candidate points drawn from `rng.seed((x, y, ...))`. Every
+/// candidate also draws a priority, and is accepted unless it's too close to
+/// an accepted candidate of higher priority. That's classic dart throwing,
+/// with the darts thrown in order of priority, but deciding a candidate only
+/// involves the candidates near it, so any region can be sampled on its own.
+///
+/// More candidates leave fewer gaps, at a proportional cost.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{
+///     spatial::{PointSampler, PoissonDisk},
+///     PureRng,
+/// };
+///
+/// let ore = PoissonDisk::new(PureRng::new("ore"), 3.).points([0., 0., 0.], [12., 12., 12.]);
+///
+/// for a in &ore {
+///     for b in &ore {
+///         let distance2: f64 = (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum();
+///         assert!(a == b || distance2 >= 9.);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PoissonDisk<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+    /// The minimum distance between points.
+    pub radius: f64,
+    /// The number of candidates in each cell. Defaults to 4.
+    pub candidates: u32,
+}
+
+impl<H> PoissonDisk<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a sampler with the given minimum distance between points.
+    pub fn new(rng: PureRandomGenerator<H>, radius: f64) -> Self {
+        Self {
+            rng,
+            radius,
+            candidates: CANDIDATES,
+        }
+    }
+}
+
+impl<H, const N: usize> PointSampler<N> for PoissonDisk<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// # Panics
+    ///
+    /// Panics if the radius isn't positive.
+    fn points(&self, min: [f64; N], max: [f64; N]) -> Vec<[f64; N]> {
+        let radius = self.radius;
+
+        Sampler::new(&self.rng, radius, radius, self.candidates, |_| radius).points(min, max)
+    }
+}
+
+/// Random points, with the minimum distance between them varying over space.
+///
+/// This works like [`PoissonDisk`], but each candidate's radius is given by a
+/// function of its position, clamped between `min_radius` and `max_radius`.
+/// Two points are never closer together than the larger of their radii. The
+/// cells are half of `min_radius` across, so the cost grows with the ratio of
+/// the radii.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{
+///     spatial::{BlueNoise, PointSampler},
+///     PureRng,
+/// };
+///
+/// // Trees thinning out to the east.
+/// let trees = BlueNoise::new(PureRng::new("trees"), 2., 4., |[x, _]: [f64; 2]| x / 10.);
+///
+/// let west = trees.points([0., 0.], [20., 20.]).len();
+/// let east = trees.points([20., 0.], [40., 20.]).len();
+/// assert!(west > east);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct BlueNoise<H, F>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+    /// The smallest minimum distance between points.
+    pub min_radius: f64,
+    /// The largest minimum distance between points.
+    pub max_radius: f64,
+    /// The number of candidates in each cell. Defaults to 4.
+    pub candidates: u32,
+    radius: F,
+}
+
+impl<H, F> BlueNoise<H, F>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a sampler with the minimum distance between points given by
+    /// `radius`, clamped between `min_radius` and `max_radius`.
+    pub fn new(rng: PureRandomGenerator<H>, min_radius: f64, max_radius: f64, radius: F) -> Self {
+        Self {
+            rng,
+            min_radius,
+            max_radius,
+            candidates: CANDIDATES,
+            radius,
+        }
+    }
+}
+
+impl<H, F, const N: usize> PointSampler<N> for BlueNoise<H, F>
+where
+    H: Hasher + Default + Clone,
+    F: Fn([f64; N]) -> f64,
+{
+    /// # Panics
+    ///
+    /// Panics if `min_radius` isn't positive, or is larger than `max_radius`.
+    fn points(&self, min: [f64; N], max: [f64; N]) -> Vec<[f64; N]> {
+        Sampler::new(
+            &self.rng,
+            self.min_radius,
+            self.max_radius,
+            self.candidates,
+            &self.radius,
+        )
+        .points(min, max)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Candidate<const N: usize> {
+    point: [f64; N],
+    radius: f64,
+    priority: u64,
+}
+
+impl<const N: usize> Candidate<N> {
+    /// Whether two candidates are too close to both be accepted.
+    fn conflicts(&self, other: &Self) -> bool {
+        let distance2: f64 = (0..N)
+            .map(|axis| {
+                (self.point[axis] - other.point[axis]) * (self.point[axis] - other.point[axis])
+            })
+            .sum();
+        let radius = self.radius.max(other.radius);
+
+        distance2 < radius * radius
+    }
+}
+
+/// A candidate's cell, and its index in the cell.
+type Id<const N: usize> = ([i64; N], u32);
+
+/// A candidate being decided, with the conflicting candidates of higher
+/// priority, and how many of them are known to be rejected.
+struct Frame<const N: usize> {
+    id: Id<N>,
+    blockers: Vec<Id<N>>,
+    rejected: usize,
+}
+
+/// The state of one query, caching the candidates and decisions made so far.
+struct Sampler<'a, H, R, const N: usize>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: &'a PureRandomGenerator<H>,
+    cell: f64,
+    candidates: u32,
+    min_radius: f64,
+    max_radius: f64,
+    radius: R,
+    cells: BTreeMap<[i64; N], Vec<Candidate<N>>>,
+    accepted: BTreeMap<Id<N>, bool>,
+}
+
+impl<'a, H, R, const N: usize> Sampler<'a, H, R, N>
+where
+    H: Hasher + Default + Clone,
+    R: Fn([f64; N]) -> f64,
+{
+    fn new(
+        rng: &'a PureRandomGenerator<H>,
+        min_radius: f64,
+        max_radius: f64,
+        candidates: u32,
+        radius: R,
+    ) -> Self {
+        assert!(min_radius > 0., "the radius must be positive");
+        assert!(
+            min_radius <= max_radius,
+            "the minimum radius can't be larger than the maximum"
+        );
+
+        Self {
+            rng,
+            cell: min_radius / 2.,
+            candidates,
+            min_radius,
+            max_radius,
+            radius,
+            cells: BTreeMap::new(),
+            accepted: BTreeMap::new(),
+        }
+    }
+
+    fn points(mut self, min: [f64; N], max: [f64; N]) -> Vec<[f64; N]> {
+        let mut points = Vec::new();
+
+        for_each_cell(cells(min, max, self.cell), |cell| {
+            for index in 0..self.candidates {
+                let point = self.cell_candidates(cell)[index as usize].point;
+                if contains(min, max, point) && self.is_accepted((cell, index)) {
+                    points.push(point);
+                }
+            }
+        });
+
+        points
+    }
+
+    /// The candidates of a cell, drawn the first time they're needed.
+    fn cell_candidates(&mut self, cell: [i64; N]) -> &[Candidate<N>] {
+        if !self.cells.contains_key(&cell) {
+            let candidates = self.generate(cell);
+            self.cells.insert(cell, candidates);
+        }
+
+        &self.cells[&cell]
+    }
+
+    fn generate(&self, cell: [i64; N]) -> Vec<Candidate<N>> {
+        let mut rng = self.rng.seed_lattice(cell);
+
+        (0..self.candidates)
+            .map(|_| {
+                let point = cell.map(|x| (x as f64 + rng.draw_unit()) * self.cell);
+                // `max` and `min` also replace NaN.
+                let radius = (self.radius)(point)
+                    .max(self.min_radius)
+                    .min(self.max_radius);
+
+                Candidate {
+                    point,
+                    radius,
+                    priority: rng.draw(),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether a candidate is accepted.
+    ///
+    /// A candidate is accepted unless a conflicting candidate of higher
+    /// priority is accepted, ie. one ordered before it by `(priority, id)`.
+    /// The chains of candidates this depends on are decided with a stack
+    /// rather than recursion, since they can be long.
+    fn is_accepted(&mut self, id: Id<N>) -> bool {
+        if let Some(&accepted) = self.accepted.get(&id) {
+            return accepted;
+        }
+
+        let mut stack = vec![self.frame(id)];
+        while let Some(frame) = stack.last_mut() {
+            let decision = loop {
+                let Some(blocker) = frame.blockers.get(frame.rejected) else {
+                    break Some(true);
+                };
+
+                match self.accepted.get(blocker) {
+                    Some(true) => break Some(false),
+                    Some(false) => frame.rejected += 1,
+                    None => break None,
+                }
+            };
+
+            if let Some(accepted) = decision {
+                self.accepted.insert(frame.id, accepted);
+                stack.pop();
+            } else {
+                let blocker = frame.blockers[frame.rejected];
+                stack.push(self.frame(blocker));
+            }
+        }
+
+        self.accepted[&id]
+    }
+
+    /// Finds the conflicting candidates of higher priority, highest first,
+    /// since they're the most likely to be accepted.
+    fn frame(&mut self, id: Id<N>) -> Frame<N> {
+        let candidate = self.cell_candidates(id.0)[id.1 as usize];
+        let key = (candidate.priority, id);
+
+        let mut blockers = Vec::new();
+        let range = cells(
+            candidate.point.map(|x| x - self.max_radius),
+            candidate.point.map(|x| x + self.max_radius),
+            self.cell,
+        );
+        for_each_cell(range, |cell| {
+            for (index, other) in (0..).zip(self.cell_candidates(cell)) {
+                if (other.priority, (cell, index)) < key && candidate.conflicts(other) {
+                    blockers.push((other.priority, (cell, index)));
+                }
+            }
+        });
+        blockers.sort_unstable();
+
+        Frame {
+            id,
+            blockers: blockers.into_iter().map(|(_, id)| id).collect(),
+            rejected: 0,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::{spatial::tests::check_seamless, PureRng};
+
+    fn min_distance2<const N: usize>(points: &[[f64; N]]) -> f64 {
+        let mut min = f64::INFINITY;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[..i] {
+                let distance2: f64 = (0..N)
+                    .map(|axis| (a[axis] - b[axis]) * (a[axis] - b[axis]))
+                    .sum();
+                min = min.min(distance2);
+            }
+        }
+
+        min
+    }
+
+    #[test]
+    fn test_poisson_disk() {
+        let disk = PoissonDisk::new(PureRng::new("disk"), 2.);
+
+        let points = disk.points([-20., -20.], [20., 20.]);
+        assert!(min_distance2(&points) >= 4.);
+        for point in &points {
+            assert!(contains([-20., -20.], [20., 20.], *point));
+        }
+
+        // A maximal packing of disks of radius 1 has about 0.7 points per
+        // unit of area here, so the density should come close.
+        let density = points.len() as f64 / 1600. * 4.;
+        assert!((0.6..0.75).contains(&density), "{density}");
+
+        check_seamless::<2>(&disk, 16.);
+    }
+
+    #[test]
+    fn test_poisson_disk_3d() {
+        let disk = PoissonDisk::new(PureRng::new("disk"), 2.);
+
+        let points = disk.points([0.; 3], [10.; 3]);
+        assert!(points.len() > 50);
+        assert!(min_distance2(&points) >= 4.);
+
+        check_seamless::<3>(&disk, 6.);
+    }
+
+    #[test]
+    fn test_blue_noise() {
+        let radius = |[x, _]: [f64; 2]| x / 4.;
+        let noise = BlueNoise::new(PureRng::new("noise"), 1., 3., radius);
+
+        let points = noise.points([0., 0.], [16., 16.]);
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[..i] {
+                let distance2 = (a[0] - b[0]) * (a[0] - b[0]) + (a[1] - b[1]) * (a[1] - b[1]);
+                let radius = radius(*a).max(radius(*b)).clamp(1., 3.);
+                assert!(distance2 >= radius * radius);
+            }
+        }
+
+        let near = points.iter().filter(|point| point[0] < 4.).count();
+        let far = points.iter().filter(|point| point[0] >= 12.).count();
+        assert!(near > far * 4, "{near} {far}");
+
+        check_seamless::<2>(&noise, 16.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_radius() {
+        PoissonDisk::new(PureRng::new("disk"), 0.).points([0., 0.], [1., 1.]);
+    }
+}
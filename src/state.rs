@@ -0,0 +1,382 @@
+//! Hasher-agnostic snapshots of generator state.
+
+use core::{
+    fmt::{self, Display, Formatter},
+    hash::Hasher,
+    str::FromStr,
+};
+
+use crate::{PureRandomGenerator, SeedLabel, STREAM_VERSION};
+
+/// Mixed in when restoring a generator, so that restored generators don't
+/// collide with ones created by seeding with a plain `u64`.
+const STATE_LABEL: SeedLabel = SeedLabel::new("pure_rng::PureRngState");
+
+/// The alphabet for base64, in its URL and filename safe variant.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// A snapshot of a generator, which can be stored or sent elsewhere and
+/// restored without relying on the hasher's own serialisation.
+///
+/// The [`Hasher`] trait gives no access to a hasher's internal state, so the
+/// snapshot holds the 64-bit value the generator would draw next, which every
+/// value it could produce is derived from. Restoring it with
+/// [`PureRandomGenerator::from_state`] therefore gives a new generator
+/// derived from that value, rather than a copy of the original. To keep the
+/// two sides in step, [`to_state`](PureRandomGenerator::to_state) returns
+/// that same generator alongside the snapshot, and the exporting side should
+/// carry on with it in place of the original.
+///
+/// Alongside that value the snapshot records the format version, the
+/// [`STREAM_VERSION`] and a fingerprint of the hasher, so that restoring it
+/// with an incompatible version of this crate or a different hasher fails
+/// rather than silently producing different values.
+///
+/// A snapshot is 17 bytes long. It can be exported as bytes, as hex with
+/// [`Display`] and [`FromStr`], or as URL-safe base64 without padding.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "rapidhash", feature = "alloc", feature = "rand_08"))]
+/// # {
+/// use pure_rng::{PureRng, PureRngState};
+///
+/// // Send the generator for a dungeon floor over the network.
+/// let floor = PureRng::new("world").seed(("floor", 3));
+/// let (state, floor) = floor.to_state();
+/// let packet = state.to_bytes();
+///
+/// let state = PureRngState::from_bytes(&packet).unwrap();
+/// let restored = PureRng::from_state(state).unwrap();
+/// assert_eq!(restored.gen::<u64>(), floor.gen::<u64>());
+///
+/// // Or store it in a save file as text.
+/// let text = state.to_base64();
+/// let state = PureRngState::from_base64(&text).unwrap();
+/// assert!(PureRng::from_state(state).unwrap() == restored);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PureRngState {
+    stream_version: u32,
+    fingerprint: u32,
+    value: u64,
+}
+
+/// An error from decoding a [`PureRngState`] or restoring a generator from
+/// one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StateError {
+    /// The encoded snapshot had the wrong length.
+    InvalidLength,
+    /// The encoded snapshot contained a character outside its alphabet, or
+    /// base64 with unused bits set.
+    InvalidCharacter,
+    /// The snapshot was written in an unknown format, probably by a newer
+    /// version of this crate.
+    UnsupportedFormat(u8),
+    /// The snapshot was taken with a different [`STREAM_VERSION`], so the
+    /// restored generator wouldn't produce the same values.
+    StreamVersion(u32),
+    /// The snapshot was taken from a generator using a different hasher.
+    HasherMismatch,
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "generator state has the wrong length"),
+            Self::InvalidCharacter => write!(f, "generator state contains an invalid character"),
+            Self::UnsupportedFormat(format) => {
+                write!(f, "generator state has unsupported format {format}")
+            }
+            Self::StreamVersion(version) => write!(
+                f,
+                "generator state is from stream version {version}, but this is version {STREAM_VERSION}"
+            ),
+            Self::HasherMismatch => write!(f, "generator state is from a different hasher"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateError {}
+
+impl PureRngState {
+    /// The version of the encoded format, stored in its first byte.
+    pub const FORMAT_VERSION: u8 = 1;
+
+    /// The length of the encoded snapshot in bytes.
+    pub const LEN: usize = 17;
+
+    /// Returns the [`STREAM_VERSION`] the snapshot was taken with.
+    pub fn stream_version(&self) -> u32 {
+        self.stream_version
+    }
+
+    /// Encodes the snapshot as bytes.
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        bytes[0] = Self::FORMAT_VERSION;
+        bytes[1..5].copy_from_slice(&self.stream_version.to_le_bytes());
+        bytes[5..9].copy_from_slice(&self.fingerprint.to_le_bytes());
+        bytes[9..].copy_from_slice(&self.value.to_le_bytes());
+
+        bytes
+    }
+
+    /// Decodes a snapshot from bytes produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StateError> {
+        let bytes: &[u8; Self::LEN] = bytes.try_into().map_err(|_| StateError::InvalidLength)?;
+        if bytes[0] != Self::FORMAT_VERSION {
+            return Err(StateError::UnsupportedFormat(bytes[0]));
+        }
+
+        Ok(Self {
+            stream_version: u32::from_le_bytes(bytes[1..5].try_into().unwrap()),
+            fingerprint: u32::from_le_bytes(bytes[5..9].try_into().unwrap()),
+            value: u64::from_le_bytes(bytes[9..].try_into().unwrap()),
+        })
+    }
+
+    /// Encodes the snapshot as lowercase hex. Equivalent to `to_string()`.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(&self) -> alloc::string::String {
+        alloc::string::ToString::to_string(self)
+    }
+
+    /// Decodes a snapshot from hex, in either case. Equivalent to `parse()`.
+    pub fn from_hex(text: &str) -> Result<Self, StateError> {
+        let text = text.as_bytes();
+        if text.len() != Self::LEN * 2 {
+            return Err(StateError::InvalidLength);
+        }
+
+        let mut bytes = [0; Self::LEN];
+        for (byte, pair) in bytes.iter_mut().zip(text.chunks_exact(2)) {
+            *byte = hex_digit(pair[0])? << 4 | hex_digit(pair[1])?;
+        }
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Encodes the snapshot as URL-safe base64, without padding.
+    #[cfg(feature = "alloc")]
+    pub fn to_base64(&self) -> alloc::string::String {
+        let bytes = self.to_bytes();
+        let mut text = alloc::string::String::with_capacity((Self::LEN * 4).div_ceil(3));
+
+        for chunk in bytes.chunks(3) {
+            let mut group = [0; 3];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+            for i in 0..=chunk.len() {
+                let index = (bits >> (18 - 6 * i)) & 0x3f;
+                text.push(BASE64[index as usize] as char);
+            }
+        }
+
+        text
+    }
+
+    /// Decodes a snapshot from URL-safe base64, without padding. The unused
+    /// low bits of the last character must be zero, so each snapshot has only
+    /// one encoding.
+    pub fn from_base64(text: &str) -> Result<Self, StateError> {
+        let text = text.as_bytes();
+        if text.len() != (Self::LEN * 4).div_ceil(3) {
+            return Err(StateError::InvalidLength);
+        }
+
+        let mut bytes = [0; Self::LEN];
+        for (chunk, group) in bytes.chunks_mut(3).zip(text.chunks(4)) {
+            let mut bits = 0;
+            for (i, &c) in group.iter().enumerate() {
+                bits |= base64_digit(c)? << (18 - 6 * i);
+            }
+            if bits & (0xff_ffff >> (8 * chunk.len())) != 0 {
+                return Err(StateError::InvalidCharacter);
+            }
+
+            let decoded = bits.to_be_bytes();
+            chunk.copy_from_slice(&decoded[1..=chunk.len()]);
+        }
+
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl Display for PureRngState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for byte in self.to_bytes() {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for PureRngState {
+    type Err = StateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+fn hex_digit(c: u8) -> Result<u8, StateError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(StateError::InvalidCharacter),
+    }
+}
+
+fn base64_digit(c: u8) -> Result<u32, StateError> {
+    BASE64
+        .iter()
+        .position(|&digit| digit == c)
+        .map(|index| index as u32)
+        .ok_or(StateError::InvalidCharacter)
+}
+
+impl<H> PureRandomGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Takes a snapshot of the generator, returning it along with the
+    /// generator that restoring it gives. See [`PureRngState`].
+    ///
+    /// The restored generator is derived from this one just as `seed()`
+    /// derives a fork, so it differs from this one. Use the returned generator
+    /// from here on wherever the two sides must agree.
+    #[must_use = "the generator restored from the state differs from this one; use the returned one"]
+    pub fn to_state(self) -> (PureRngState, Self) {
+        let value = self.hasher.finish();
+        let state = PureRngState {
+            stream_version: STREAM_VERSION,
+            fingerprint: Self::fingerprint(),
+            value,
+        };
+
+        (state, Self::restore(value))
+    }
+
+    /// Restores a generator from a snapshot, giving the same generator as
+    /// [`to_state`](Self::to_state) returned where the snapshot was taken.
+    ///
+    /// Fails if the snapshot was taken with a different [`STREAM_VERSION`] or
+    /// hasher.
+    pub fn from_state(state: PureRngState) -> Result<Self, StateError> {
+        if state.stream_version != STREAM_VERSION {
+            return Err(StateError::StreamVersion(state.stream_version));
+        }
+        if state.fingerprint != Self::fingerprint() {
+            return Err(StateError::HasherMismatch);
+        }
+
+        Ok(Self::restore(state.value))
+    }
+
+    fn restore(value: u64) -> Self {
+        Self::default().seed_label(STATE_LABEL).seed(value)
+    }
+
+    /// Identifies the hasher by the first value drawn from its default state.
+    fn fingerprint() -> u32 {
+        (Self::default().seed_label(STATE_LABEL).hasher.finish() >> 32) as u32
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash", feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{hashers::SplitMixPureRng, PureRng};
+
+    #[test]
+    fn test_restore() {
+        let original = PureRng::new(1234).seed("floor");
+        let (state, rng) = original.to_state();
+        let restored = PureRng::from_state(state).unwrap();
+
+        assert!(restored == rng);
+        assert!(rng != original);
+        assert_eq!(restored.to_state().0, rng.to_state().0);
+        assert!(original.to_state().0 != original.seed(1).to_state().0);
+        assert!(rng.to_state().0 != state);
+    }
+
+    #[test]
+    fn test_encodings() {
+        for seed in 0..20 {
+            let (state, _) = PureRng::new(seed).to_state();
+
+            assert_eq!(PureRngState::from_bytes(&state.to_bytes()), Ok(state));
+            assert_eq!(state.to_hex().len(), 34);
+            assert_eq!(state.to_hex().parse(), Ok(state));
+            assert_eq!(
+                PureRngState::from_hex(&state.to_hex().to_uppercase()),
+                Ok(state)
+            );
+            assert_eq!(state.to_base64().len(), 23);
+            assert_eq!(PureRngState::from_base64(&state.to_base64()), Ok(state));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let (state, _) = PureRng::new(1234).to_state();
+        let bytes = state.to_bytes();
+
+        assert_eq!(
+            PureRngState::from_bytes(&bytes[1..]),
+            Err(StateError::InvalidLength)
+        );
+        assert_eq!(
+            PureRngState::from_hex(&state.to_hex()[2..]),
+            Err(StateError::InvalidLength)
+        );
+        assert_eq!(
+            PureRngState::from_hex(&state.to_hex().replace(|_| true, "g")),
+            Err(StateError::InvalidCharacter)
+        );
+        assert_eq!(
+            PureRngState::from_base64(&state.to_base64().replace(|_| true, "+")),
+            Err(StateError::InvalidCharacter)
+        );
+
+        // The last character carries two unused bits, which must be zero.
+        let mut text = state.to_base64();
+        let last = text.pop().unwrap() as u8;
+        let last = BASE64.iter().position(|&c| c == last).unwrap();
+        text.push(BASE64[last + 1] as char);
+        assert_eq!(
+            PureRngState::from_base64(&text),
+            Err(StateError::InvalidCharacter)
+        );
+
+        let mut future = bytes;
+        future[0] = 2;
+        assert_eq!(
+            PureRngState::from_bytes(&future),
+            Err(StateError::UnsupportedFormat(2))
+        );
+
+        let mut old = bytes;
+        old[1] = 0;
+        let old = PureRngState::from_bytes(&old).unwrap();
+        assert_eq!(
+            PureRng::from_state(old).map(|_| ()),
+            Err(StateError::StreamVersion(0))
+        );
+
+        assert_eq!(
+            SplitMixPureRng::from_state(state).map(|_| ()),
+            Err(StateError::HasherMismatch)
+        );
+    }
+}
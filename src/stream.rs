@@ -0,0 +1,135 @@
+//! A fast conventional generator for bulk work.
+
+/// A conventional stream generator, using the xoshiro256++ algorithm.
+///
+/// Every value drawn from a [`PureRandomGenerator`](crate::PureRandomGenerator)
+/// costs a full hash, which adds up when shuffling a large deck or drawing
+/// millions of values from `sample_iter`. Converting the generator with
+/// `into_stream::<PureStream>()` seeds one of these from its state, so bulk
+/// work stays deterministic but draws values around three times faster. The
+/// trade-off is that the stream is no longer pure: it can't be forked with
+/// `seed()`, and each value depends on how many were drawn before it.
+///
+/// Implements `RngCore` and `SeedableRng` for every enabled `rand` version.
+/// Its values are covered by the value-stability policy described on
+/// [`STREAM_VERSION`](crate::STREAM_VERSION).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+/// # {
+/// use pure_rng::{PureRng, PureStream};
+/// use rand::seq::SliceRandom;
+///
+/// let rng = PureRng::new("world").seed("deck");
+/// let mut cards: Vec<u32> = (0..100_000).collect();
+/// cards.shuffle(&mut rng.into_stream::<PureStream>());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PureStream {
+    state: [u64; 4],
+}
+
+/// The state used in place of an all-zero seed, from which xoshiro would only
+/// ever produce zeroes. These are the first outputs of SplitMix64 seeded with
+/// zero.
+const ZERO_SEED_STATE: [u64; 4] = [
+    0xe220a8397b1dcdaf,
+    0x6e789e6aa1b965f4,
+    0x06c45d188009454f,
+    0xf88bb8a8724c81ec,
+];
+
+impl PureStream {
+    /// Creates a stream from a seed of four little-endian `u64`s.
+    pub(crate) fn from_seed_bytes(seed: [u8; 32]) -> Self {
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(8)) {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        if state == [0; 4] {
+            state = ZERO_SEED_STATE;
+        }
+
+        Self { state }
+    }
+
+    /// Draws the next value.
+    pub(crate) fn next(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s0.wrapping_add(*s3).rotate_left(23).wrapping_add(*s0);
+
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(words: [u64; 4]) -> [u8; 32] {
+        let mut seed = [0; 32];
+        for (bytes, word) in seed.chunks_exact_mut(8).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+
+        seed
+    }
+
+    #[test]
+    fn test_reference() {
+        // From the reference implementation of xoshiro256++.
+        let mut stream = PureStream::from_seed_bytes(seed([1, 2, 3, 4]));
+        let values: Vec<u64> = (0..6).map(|_| stream.next()).collect();
+
+        assert_eq!(
+            values,
+            [
+                41943041,
+                58720359,
+                3588806011781223,
+                3591011842654386,
+                9228616714210784205,
+                9973669472204895162,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_seed() {
+        let mut stream = PureStream::from_seed_bytes([0; 32]);
+
+        assert_eq!(stream, PureStream::from_seed_bytes(seed(ZERO_SEED_STATE)));
+        assert_ne!(stream.next(), 0);
+    }
+
+    #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    #[test]
+    fn test_into_stream() {
+        use rand::{RngCore, SeedableRng};
+
+        use crate::PureRng;
+
+        let rng = PureRng::new(1234);
+
+        let mut seed = [0; 32];
+        let mut copy = rng;
+        copy.fill_bytes(&mut seed);
+        let expected = PureStream::from_seed(seed);
+
+        assert_eq!(rng.into_stream::<PureStream>(), expected);
+        assert_ne!(rng.seed(1).into_stream::<PureStream>(), expected);
+    }
+}
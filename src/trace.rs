@@ -0,0 +1,408 @@
+//! Seed-path tracing for diagnosing divergence, enabled by the `trace`
+//! feature.
+//!
+//! A [`PureRandomGenerator`] only holds an opaque hasher, so when two runs
+//! produce different values there is no way to tell which chain of `seed()`
+//! calls was involved. A [`TracingGenerator`] wraps a generator and records the
+//! label passed to every `seed()` call along with the number of values drawn,
+//! so that a generated value can be reported alongside the path that produced
+//! it.
+//!
+//! Tracing doesn't affect the generated values: a traced value is always
+//! identical to the one produced by the equivalent untraced calls.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+//! # {
+//! use pure_rng::trace::TracingPureRng;
+//!
+//! let rng = TracingPureRng::new("world");
+//! let health = rng
+//!     .seed("monster generation")
+//!     .seed("red monster")
+//!     .seed("health")
+//!     .gen_range(10..20);
+//!
+//! assert_eq!(
+//!     health.path.to_string(),
+//!     "world/monster generation/red monster/health"
+//! );
+//! println!("{health}"); // eg. world/monster generation/red monster/health [1 draw] = 17
+//! # }
+//! ```
+
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{PureRandomGenerator, SeedLabel};
+
+/// A [`TracingGenerator`] using the default hasher.
+#[cfg(feature = "rapidhash")]
+pub type TracingPureRng = TracingGenerator<crate::StableHasher<rapidhash::RapidHasher>>;
+
+/// The chain of labels passed to `seed()` to arrive at a generator.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SeedPath(Vec<Arc<str>>);
+
+impl SeedPath {
+    /// Returns the labels in the path, from the root downwards.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|label| &**label)
+    }
+
+    /// Returns the number of labels in the path.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the path has no labels.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn push(&self, label: Arc<str>) -> Self {
+        let mut path = self.clone();
+        path.0.push(label);
+
+        path
+    }
+}
+
+impl Display for SeedPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, label) in self.labels().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(label)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a seed value for use as a label. Strings and chars are shown
+/// without their surrounding quotes.
+fn label(hashable: &impl Debug) -> Arc<str> {
+    let label = format!("{hashable:?}");
+
+    let unquoted = label
+        .strip_prefix('"')
+        .and_then(|l| l.strip_suffix('"'))
+        .or_else(|| label.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')));
+
+    match unquoted {
+        Some(unquoted) if !unquoted.is_empty() => unquoted.into(),
+        _ => label.into(),
+    }
+}
+
+/// A generated value, along with the seed path and number of draws that
+/// produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Traced<T> {
+    pub value: T,
+    pub path: SeedPath,
+    pub draws: u64,
+}
+
+impl<T> Traced<T> {
+    /// Discards the trace, returning the value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Debug> Display for Traced<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let plural = if self.draws == 1 { "" } else { "s" };
+
+        write!(
+            f,
+            "{} [{} draw{}] = {:?}",
+            self.path, self.draws, plural, self.value
+        )
+    }
+}
+
+/// Wraps a [`PureRandomGenerator`], recording the seed path used to reach it
+/// and the number of values drawn from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracingGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    rng: PureRandomGenerator<H>,
+    path: SeedPath,
+    draws: u64,
+}
+
+impl<H> TracingGenerator<H>
+where
+    H: Hasher + Default + Clone,
+{
+    /// Creates a new generator with the given hashable value as the seed,
+    /// which also becomes the first label in the path.
+    ///
+    /// See [`PureRandomGenerator::new`].
+    pub fn new(hashable: impl Hash + Debug) -> Self {
+        let path = SeedPath::default().push(label(&hashable));
+
+        Self::from_generator(PureRandomGenerator::new(hashable), path)
+    }
+
+    /// Starts tracing an existing generator from the given path.
+    pub fn from_generator(rng: PureRandomGenerator<H>, path: SeedPath) -> Self {
+        Self {
+            rng,
+            path,
+            draws: 0,
+        }
+    }
+
+    /// Forks the generator, recording the `Debug` form of the given value in
+    /// the path.
+    ///
+    /// See [`PureRandomGenerator::seed`].
+    pub fn seed(&self, hashable: impl Hash + Debug) -> Self {
+        let name = label(&hashable);
+
+        self.seed_named(hashable, name)
+    }
+
+    /// Forks the generator, recording the given name in the path instead of
+    /// the seed value itself. Use this for values without a `Debug`
+    /// implementation, or with an unhelpful one.
+    pub fn seed_named(&self, hashable: impl Hash, name: impl Into<Arc<str>>) -> Self {
+        Self::from_generator(self.rng.seed(hashable), self.path.push(name.into()))
+    }
+
+    /// Forks the generator with a pre-hashed label, recording the label's
+    /// name in the path.
+    ///
+    /// See [`PureRandomGenerator::seed_label`].
+    pub fn seed_label(&self, label: SeedLabel) -> Self {
+        Self::from_generator(
+            self.rng.seed_label(label),
+            self.path.push(label.name().into()),
+        )
+    }
+
+    /// Returns the path taken to reach this generator.
+    pub fn path(&self) -> &SeedPath {
+        &self.path
+    }
+
+    /// Returns the number of values drawn from this generator so far.
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Returns the underlying untraced generator.
+    pub fn into_inner(self) -> PureRandomGenerator<H> {
+        self.rng
+    }
+
+    /// Generates a value with the given function, and returns it alongside
+    /// the trace. This is the escape hatch for any `rand` API without a
+    /// dedicated wrapper here.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "rapidhash", feature = "rand_08"))]
+    /// # {
+    /// use pure_rng::trace::TracingPureRng;
+    /// use rand::{distributions::Alphanumeric, Rng};
+    ///
+    /// let name: String = TracingPureRng::new("world")
+    ///     .seed("name")
+    ///     .generate(|rng| rng.sample_iter(Alphanumeric).take(8).map(char::from).collect())
+    ///     .value;
+    /// # }
+    /// ```
+    pub fn generate<T>(mut self, f: impl FnOnce(&mut Self) -> T) -> Traced<T> {
+        let value = f(&mut self);
+
+        Traced {
+            value,
+            path: self.path,
+            draws: self.draws,
+        }
+    }
+
+    #[cfg_attr(not(any(feature = "rand_08", feature = "rand_09")), allow(dead_code))]
+    fn draw(&mut self) -> u64 {
+        self.draws += 1;
+        self.rng.draw()
+    }
+}
+
+#[cfg(feature = "rand_08")]
+mod rand_08 {
+    use std::hash::Hasher;
+
+    use rand::{
+        distributions::{
+            uniform::{SampleRange, SampleUniform},
+            Distribution, Standard,
+        },
+        Rng, RngCore,
+    };
+
+    use super::{Traced, TracingGenerator};
+
+    impl<H> RngCore for TracingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        fn next_u32(&mut self) -> u32 {
+            self.draw() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.draw()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    /// Traced wrappers for the `rand` 0.8 [`Rng`] trait functions.
+    impl<H> TracingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        /// See [`PureRandomGenerator::gen`](crate::PureRandomGenerator::gen).
+        pub fn gen<T>(self) -> Traced<T>
+        where
+            Standard: Distribution<T>,
+        {
+            self.generate(|rng| Rng::gen(rng))
+        }
+
+        /// See [`PureRandomGenerator::gen_range`](crate::PureRandomGenerator::gen_range).
+        pub fn gen_range<T, Q>(self, range: Q) -> Traced<T>
+        where
+            T: SampleUniform,
+            Q: SampleRange<T>,
+        {
+            self.generate(|rng| Rng::gen_range(rng, range))
+        }
+
+        /// See [`PureRandomGenerator::sample`](crate::PureRandomGenerator::sample).
+        pub fn sample<T, D: Distribution<T>>(self, distr: D) -> Traced<T> {
+            self.generate(|rng| Rng::sample(rng, distr))
+        }
+    }
+}
+
+#[cfg(feature = "rand_09")]
+mod rand_09 {
+    use std::hash::Hasher;
+
+    use rand09::{
+        distr::{
+            uniform::{SampleRange, SampleUniform},
+            Distribution, StandardUniform,
+        },
+        rand_core, Rng, RngCore,
+    };
+
+    use super::{Traced, TracingGenerator};
+
+    impl<H> RngCore for TracingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        fn next_u32(&mut self) -> u32 {
+            self.draw() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.draw()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest);
+        }
+    }
+
+    /// Traced wrappers for the `rand` 0.9 [`Rng`] trait functions.
+    impl<H> TracingGenerator<H>
+    where
+        H: Hasher + Default + Clone,
+    {
+        /// See [`PureRandomGenerator::random`](crate::PureRandomGenerator::random).
+        pub fn random<T>(self) -> Traced<T>
+        where
+            StandardUniform: Distribution<T>,
+        {
+            self.generate(|rng| Rng::random(rng))
+        }
+
+        /// See [`PureRandomGenerator::random_range`](crate::PureRandomGenerator::random_range).
+        pub fn random_range<T, Q>(self, range: Q) -> Traced<T>
+        where
+            T: SampleUniform,
+            Q: SampleRange<T>,
+        {
+            self.generate(|rng| Rng::random_range(rng, range))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash", feature = "rand_08"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    #[test]
+    fn test_path() {
+        let rng = TracingPureRng::new("world");
+        let traced = rng.seed("monster").seed(3).seed('x').seed_named((), "unit");
+
+        assert_eq!(traced.path().to_string(), "world/monster/3/x/unit");
+        assert_eq!(traced.path().len(), 5);
+        assert_eq!(rng.seed("").path().to_string(), "world/\"\"");
+
+        let labelled = rng.seed_label(crate::seed_label!("tile")).gen::<u64>();
+        assert_eq!(labelled.path.to_string(), "world/tile");
+        assert_eq!(
+            labelled.value,
+            PureRng::new("world")
+                .seed_label(crate::seed_label!("tile"))
+                .gen::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_values_match_untraced() {
+        let traced = TracingPureRng::new("world")
+            .seed("health")
+            .gen_range(0..100);
+        let untraced: i32 = PureRng::new("world").seed("health").gen_range(0..100);
+
+        assert_eq!(traced.value, untraced);
+        assert_eq!(traced.draws, 1);
+
+        let traced = TracingPureRng::new("world").gen::<[u64; 3]>();
+        assert_eq!(traced.value, PureRng::new("world").gen::<[u64; 3]>());
+        assert_eq!(traced.draws, 3);
+        assert_eq!(
+            traced.to_string(),
+            format!("world [3 draws] = {:?}", traced.value)
+        );
+    }
+}
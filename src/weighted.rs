@@ -0,0 +1,624 @@
+//! Weighted choice with a precomputed alias table.
+
+use alloc::vec::Vec;
+use core::{
+    fmt::{self, Display, Formatter},
+    hash::Hasher,
+    ops::Range,
+};
+
+use crate::{distributions::PureDistribution, PureRandomGenerator};
+
+/// A table of weighted items, sampled in constant time.
+///
+/// `SlicePureRandom::choose_weighted` sums the weights on every call. This
+/// instead builds alias tables once, with Vose's method, so each sample takes
+/// four draws from the generator whatever the number of items. The items are
+/// grouped into blocks of 64, and an alias table over the blocks' total
+/// weights chooses a block before the block's own alias table chooses an
+/// item. Each alias table takes two draws: one choosing a column uniformly,
+/// and one choosing between the column's entry and its alias.
+///
+/// Samples are a pure function of the generator and the weights, in order. A
+/// table updated to some weights samples exactly like a new table built with
+/// them. An update can change the item sampled by any generator, though, not
+/// only by those which sampled, or now sample, the updated items. If some
+/// samples must survive an update, store them rather than sampling again.
+///
+/// Updates are incremental: [`set_weight`](Self::set_weight),
+/// [`set_weights`](Self::set_weights) and [`push`](Self::push) only rebuild
+/// the alias tables of the blocks they change and the table over the blocks.
+/// Changing one weight of a 5,000 item table rebuilds about 140 entries
+/// rather than 5,000.
+///
+/// With the `serde` feature a table serializes as a list of `item` and
+/// `weight` pairs, and the alias table is rebuilt when it's deserialized.
+///
+/// The samples are independent of the `rand` version, and are covered by the
+/// value-stability policy described on [`STREAM_VERSION`](crate::STREAM_VERSION).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rapidhash")]
+/// # {
+/// use pure_rng::{PureRng, PureWeightedTable};
+///
+/// let mut spawns = PureWeightedTable::new(vec!["zombie", "skeleton", "creeper"], vec![10., 5., 1.])
+///     .unwrap();
+///
+/// let rng = PureRng::new("world").seed(("spawn", 12, -7));
+/// let mob = *spawns.sample(rng);
+/// assert_eq!(mob, *spawns.sample(rng));
+///
+/// // Creepers become more common at night.
+/// spawns.set_weight(2, 8.).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PureWeightedTable<T> {
+    items: Vec<T>,
+    weights: Vec<f64>,
+    total: f64,
+    /// The alias tables of the blocks, one after another. Aliases are indices
+    /// within the block.
+    items_table: AliasTable,
+    /// The total weight of each block.
+    block_totals: Vec<f64>,
+    /// The alias table choosing a block.
+    blocks_table: AliasTable,
+}
+
+/// The number of items in each block of a [`PureWeightedTable`].
+const BLOCK: usize = 64;
+
+/// An alias table, or several laid out one after another.
+#[derive(Debug, Clone, PartialEq)]
+struct AliasTable {
+    /// The probability of each column choosing its own entry rather than its
+    /// alias.
+    probabilities: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+/// An error from building a [`PureWeightedTable`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WeightedTableError {
+    /// There were no items.
+    Empty,
+    /// The numbers of items and weights were different.
+    LengthMismatch,
+    /// The weight at the given index was negative, infinite or NaN.
+    InvalidWeight(usize),
+    /// The weights added up to zero, or overflowed.
+    InvalidTotal,
+}
+
+impl Display for WeightedTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "weighted table has no items"),
+            Self::LengthMismatch => write!(
+                f,
+                "weighted table has different numbers of items and weights"
+            ),
+            Self::InvalidWeight(index) => write!(f, "weight {index} is negative or not finite"),
+            Self::InvalidTotal => write!(f, "weights must have a positive, finite total"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WeightedTableError {}
+
+impl<T> PureWeightedTable<T> {
+    /// Creates a table where the item at each index has the weight at the
+    /// same index.
+    pub fn new(items: Vec<T>, weights: Vec<f64>) -> Result<Self, WeightedTableError> {
+        if items.len() != weights.len() {
+            return Err(WeightedTableError::LengthMismatch);
+        }
+        if items.is_empty() {
+            return Err(WeightedTableError::Empty);
+        }
+
+        let len = weights.len();
+        let mut table = Self {
+            items,
+            weights,
+            total: 0.,
+            items_table: AliasTable::new(len),
+            block_totals: Vec::new(),
+            blocks_table: AliasTable::new(0),
+        };
+        table.rebuild(0..len)?;
+
+        Ok(table)
+    }
+
+    /// Creates a table from pairs of items and their weights.
+    pub fn from_pairs(
+        pairs: impl IntoIterator<Item = (T, f64)>,
+    ) -> Result<Self, WeightedTableError> {
+        let (items, weights) = pairs.into_iter().unzip();
+
+        Self::new(items, weights)
+    }
+
+    /// Samples an item.
+    pub fn sample<H>(&self, rng: PureRandomGenerator<H>) -> &T
+    where
+        H: Hasher + Default + Clone,
+    {
+        &self.items[self.sample_index(rng)]
+    }
+
+    /// Samples the index of an item.
+    pub fn sample_index<H>(&self, mut rng: PureRandomGenerator<H>) -> usize
+    where
+        H: Hasher + Default + Clone,
+    {
+        let block = self
+            .blocks_table
+            .sample(0..self.block_totals.len(), &mut rng);
+        let start = block * BLOCK;
+        let end = self.items.len().min(start + BLOCK);
+
+        start + self.items_table.sample(start..end, &mut rng)
+    }
+
+    /// The number of items in the table.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the table is empty, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The items in the table.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The weights of the items.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// The sum of the weights.
+    pub fn total_weight(&self) -> f64 {
+        self.total
+    }
+
+    /// The probability of sampling the item at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn probability(&self, index: usize) -> f64 {
+        self.weights[index] / self.total
+    }
+
+    /// Changes the weight of the item at the given index. On error the table
+    /// is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_weight(&mut self, index: usize, weight: f64) -> Result<(), WeightedTableError> {
+        self.set_weights([(index, weight)])
+    }
+
+    /// Changes the weights of several items, given as pairs of indices and
+    /// weights. On error the table is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn set_weights(
+        &mut self,
+        updates: impl IntoIterator<Item = (usize, f64)>,
+    ) -> Result<(), WeightedTableError> {
+        let updates: Vec<(usize, f64)> = updates.into_iter().collect();
+        let len = self.len();
+        if let Some(&(index, _)) = updates.iter().find(|(index, _)| *index >= len) {
+            panic!("index out of bounds: the len is {len} but the index is {index}");
+        }
+
+        // Keep the old weights, in case the new ones turn out to be invalid.
+        let mut previous = Vec::with_capacity(updates.len());
+        for &(index, weight) in &updates {
+            previous.push((index, self.weights[index]));
+            self.weights[index] = weight;
+        }
+
+        let result = self.rebuild(updates.iter().map(|&(index, _)| index));
+        if result.is_err() {
+            for (index, weight) in previous.into_iter().rev() {
+                self.weights[index] = weight;
+            }
+        }
+
+        result
+    }
+
+    /// Adds an item to the end of the table. On error the table is left
+    /// unchanged.
+    pub fn push(&mut self, item: T, weight: f64) -> Result<(), WeightedTableError> {
+        self.weights.push(weight);
+        self.items_table.probabilities.push(0.);
+        self.items_table.aliases.push(0);
+
+        let result = self.rebuild([self.len()]);
+        if result.is_ok() {
+            self.items.push(item);
+        } else {
+            self.weights.pop();
+            self.items_table.probabilities.pop();
+            self.items_table.aliases.pop();
+        }
+
+        result
+    }
+
+    /// Rebuilds the alias tables of the blocks holding the given items, and
+    /// of the blocks themselves, once their weights have changed. On error
+    /// only the weights have been changed.
+    fn rebuild(
+        &mut self,
+        changed: impl IntoIterator<Item = usize>,
+    ) -> Result<(), WeightedTableError> {
+        let mut blocks = Vec::new();
+        for index in changed {
+            let weight = self.weights[index];
+            if !(weight >= 0. && weight.is_finite()) {
+                return Err(WeightedTableError::InvalidWeight(index));
+            }
+            blocks.push(index / BLOCK);
+        }
+        blocks.sort_unstable();
+        blocks.dedup();
+
+        let mut block_totals = self.block_totals.clone();
+        block_totals.resize(self.weights.len().div_ceil(BLOCK), 0.);
+        for &block in &blocks {
+            block_totals[block] = self.weights[block_range(block, self.weights.len())]
+                .iter()
+                .sum();
+        }
+
+        let total: f64 = block_totals.iter().sum();
+        if !(total > 0. && total.is_finite()) {
+            return Err(WeightedTableError::InvalidTotal);
+        }
+
+        for &block in &blocks {
+            let range = block_range(block, self.weights.len());
+            self.items_table
+                .fill(range.clone(), &self.weights[range], block_totals[block]);
+        }
+
+        self.blocks_table = AliasTable::new(block_totals.len());
+        self.blocks_table
+            .fill(0..block_totals.len(), &block_totals, total);
+        self.block_totals = block_totals;
+        self.total = total;
+
+        Ok(())
+    }
+}
+
+/// The indices of the items in the given block, out of `len` items.
+fn block_range(block: usize, len: usize) -> Range<usize> {
+    let start = block * BLOCK;
+
+    start..len.min(start + BLOCK)
+}
+
+/// Samples a clone of an item.
+impl<T: Clone> PureDistribution<T> for PureWeightedTable<T> {
+    fn sample<H>(&self, rng: PureRandomGenerator<H>) -> T
+    where
+        H: Hasher + Default + Clone,
+    {
+        PureWeightedTable::sample(self, rng).clone()
+    }
+}
+
+impl AliasTable {
+    /// A table of `len` columns, to be filled in.
+    fn new(len: usize) -> Self {
+        Self {
+            probabilities: alloc::vec![0.; len],
+            aliases: alloc::vec![0; len],
+        }
+    }
+
+    /// Samples the table laid out over the given columns, returning an index
+    /// within it.
+    fn sample<H>(&self, columns: Range<usize>, rng: &mut PureRandomGenerator<H>) -> usize
+    where
+        H: Hasher + Default + Clone,
+    {
+        let column = rng.draw_below(columns.len() as u64) as usize;
+        let coin = rng.draw_unit();
+
+        if coin < self.probabilities[columns.start + column] {
+            column
+        } else {
+            self.aliases[columns.start + column]
+        }
+    }
+
+    /// Fills in the given columns with an alias table for `weights`, which add
+    /// up to `total`, using Vose's method.
+    fn fill(&mut self, columns: Range<usize>, weights: &[f64], total: f64) {
+        let probabilities = &mut self.probabilities[columns.clone()];
+        let aliases = &mut self.aliases[columns];
+
+        // Scale the weights so they average 1, then fill each column up to 1
+        // by pairing an entry below 1 with one above it. Dividing first keeps
+        // the scale finite even for subnormal totals.
+        let len = weights.len() as f64;
+        for (probability, weight) in probabilities.iter_mut().zip(weights) {
+            *probability = if total > 0. { weight / total * len } else { 0. };
+        }
+        for (index, alias) in aliases.iter_mut().enumerate() {
+            *alias = index;
+        }
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..weights.len()).partition(|&index| probabilities[index] < 1.);
+
+        while let (Some(&less), Some(&more)) = (small.last(), large.last()) {
+            small.pop();
+            aliases[less] = more;
+            probabilities[more] = (probabilities[more] + probabilities[less]) - 1.;
+
+            if probabilities[more] < 1. {
+                large.pop();
+                small.push(more);
+            }
+        }
+
+        // Anything left over is within rounding error of 1, except entries
+        // which can never be chosen.
+        let heaviest = (0..weights.len())
+            .reduce(|a, b| if weights[b] > weights[a] { b } else { a })
+            .unwrap_or(0);
+        for index in small.into_iter().chain(large) {
+            if weights[index] > 0. {
+                probabilities[index] = 1.;
+            } else {
+                probabilities[index] = 0.;
+                aliases[index] = heaviest;
+            }
+        }
+    }
+}
+
+/// One entry of the serialized form of a [`PureWeightedTable`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct EntryRepr<T> {
+    item: T,
+    weight: f64,
+}
+
+/// A borrowed [`EntryRepr`], for serializing without cloning.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct EntryReprRef<'a, T> {
+    item: &'a T,
+    weight: f64,
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for PureWeightedTable<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.items
+                .iter()
+                .zip(&self.weights)
+                .map(|(item, &weight)| EntryReprRef { item, weight }),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for PureWeightedTable<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entries = Vec::<EntryRepr<T>>::deserialize(deserializer)?;
+
+        Self::from_pairs(entries.into_iter().map(|entry| (entry.item, entry.weight)))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "rapidhash"))]
+mod tests {
+    use super::*;
+    use crate::PureRng;
+
+    /// The share of each entry of the alias table laid out over `columns`.
+    fn shares(alias: &AliasTable, columns: Range<usize>) -> Vec<f64> {
+        let n = columns.len() as f64;
+        let mut shares = vec![0.; columns.len()];
+        for (column, index) in columns.enumerate() {
+            shares[column] += alias.probabilities[index] / n;
+            shares[alias.aliases[index]] += (1. - alias.probabilities[index]) / n;
+        }
+
+        shares
+    }
+
+    /// Checks that the alias tables give each item the right probability, by
+    /// adding up each column's share.
+    fn check_exact<T>(table: &PureWeightedTable<T>) {
+        let blocks = shares(&table.blocks_table, 0..table.block_totals.len());
+
+        for (block, block_share) in blocks.into_iter().enumerate() {
+            let range = block_range(block, table.len());
+            let start = range.start;
+
+            for (index, share) in shares(&table.items_table, range).into_iter().enumerate() {
+                let probability = table.probability(start + index);
+                assert!((block_share * share - probability).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        let weights: Vec<f64> = (0..5000).map(|i| ((i * 7919) % 1000) as f64 / 7.).collect();
+        let table = PureWeightedTable::new((0..5000).collect(), weights).unwrap();
+        check_exact(&table);
+
+        let table = PureWeightedTable::new(vec!['a', 'b', 'c', 'd'], vec![0., 1., 0., 3.]).unwrap();
+        check_exact(&table);
+
+        // A whole block of zero weights is never chosen.
+        let weights: Vec<f64> = (0..200).map(|i| if i < 100 { 0. } else { 1. }).collect();
+        let table = PureWeightedTable::new((0..200).collect(), weights).unwrap();
+        check_exact(&table);
+
+        // The weights are scaled without overflowing, even if their total is
+        // subnormal.
+        let table = PureWeightedTable::new(vec!['a', 'b'], vec![1e-320, 2e-320]).unwrap();
+        check_exact(&table);
+        assert!((table.probability(1) - 2. / 3.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let table =
+            PureWeightedTable::from_pairs([('a', 1.), ('b', 0.), ('c', 2.), ('d', 7.)]).unwrap();
+        let rng = PureRng::new("frequencies");
+
+        let mut counts = [0u32; 4];
+        for i in 0..100_000u32 {
+            counts[table.sample_index(rng.seed(i))] += 1;
+        }
+
+        assert_eq!(counts[1], 0);
+        for (count, expected) in counts.into_iter().zip([10_000, 0, 20_000, 70_000]) {
+            assert!(count.abs_diff(expected) < 1000, "{counts:?}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        use WeightedTableError::*;
+
+        assert_eq!(PureWeightedTable::<u8>::new(vec![], vec![]), Err(Empty));
+        assert_eq!(PureWeightedTable::new(vec![1], vec![]), Err(LengthMismatch));
+        assert_eq!(
+            PureWeightedTable::new(vec![1, 2], vec![1., -1.]),
+            Err(InvalidWeight(1))
+        );
+        assert_eq!(
+            PureWeightedTable::new(vec![1], vec![f64::NAN]),
+            Err(InvalidWeight(0))
+        );
+        assert_eq!(
+            PureWeightedTable::new(vec![1, 2], vec![0., 0.]),
+            Err(InvalidTotal)
+        );
+        assert_eq!(
+            PureWeightedTable::new(vec![1, 2], vec![f64::MAX, f64::MAX]),
+            Err(InvalidTotal)
+        );
+    }
+
+    #[test]
+    fn test_updates() {
+        let mut table = PureWeightedTable::new(vec!['a', 'b'], vec![1., 1.]).unwrap();
+
+        table.set_weight(0, 3.).unwrap();
+        table.push('c', 4.).unwrap();
+        assert_eq!(
+            table,
+            PureWeightedTable::new(vec!['a', 'b', 'c'], vec![3., 1., 4.]).unwrap()
+        );
+        assert_eq!(table.probability(2), 0.5);
+
+        let before = table.clone();
+        assert_eq!(
+            table.set_weights([(0, 0.), (1, f64::INFINITY)]),
+            Err(WeightedTableError::InvalidWeight(1))
+        );
+        assert_eq!(
+            table.set_weights([(0, 0.), (1, 0.), (2, 0.)]),
+            Err(WeightedTableError::InvalidTotal)
+        );
+        assert_eq!(
+            table.push('d', -1.),
+            Err(WeightedTableError::InvalidWeight(3))
+        );
+        assert_eq!(table, before);
+    }
+
+    #[test]
+    fn test_large_updates() {
+        let weight = |i: usize| ((i * 7919) % 1000) as f64 / 7.;
+        let mut table =
+            PureWeightedTable::new((0..5000).collect(), (0..5000).map(weight).collect()).unwrap();
+        let mut weights = table.weights().to_vec();
+
+        table.set_weight(70, 1000.).unwrap();
+        table
+            .set_weights([(0, 0.), (4999, 3.), (1234, 0.5), (0, 2.)])
+            .unwrap();
+        for i in 5000..5100 {
+            table.push(i, weight(i)).unwrap();
+        }
+        for (index, weight) in [(70, 1000.), (0, 2.), (4999, 3.), (1234, 0.5)] {
+            weights[index] = weight;
+        }
+        weights.extend((5000..5100).map(weight));
+
+        check_exact(&table);
+        assert_eq!(
+            table,
+            PureWeightedTable::new((0..5100).collect(), weights).unwrap()
+        );
+
+        let before = table.clone();
+        assert_eq!(
+            table.set_weights([(3, 1.), (4000, f64::NAN)]),
+            Err(WeightedTableError::InvalidWeight(4000))
+        );
+        assert_eq!(table, before);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_out_of_bounds() {
+        let mut table = PureWeightedTable::new(vec!['a', 'b'], vec![1., 1.]).unwrap();
+        let _ = table.set_weights([(0, 2.), (2, 1.)]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let table = PureWeightedTable::new(
+            vec!["zombie".to_string(), "creeper".to_string()],
+            vec![3., 1.],
+        )
+        .unwrap();
+
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"item":"zombie","weight":3.0},{"item":"creeper","weight":1.0}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PureWeightedTable<String>>(&json).unwrap(),
+            table
+        );
+
+        let invalid = r#"[{"item":"zombie","weight":-3.0}]"#;
+        assert!(serde_json::from_str::<PureWeightedTable<String>>(invalid).is_err());
+    }
+}
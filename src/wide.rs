@@ -0,0 +1,189 @@
+//! A generator carrying 128 bits of state between draws.
+
+use core::hash::Hash;
+
+use crate::{Hasher128, SeedLabel};
+
+/// A variant of [`PureRandomGenerator`](crate::PureRandomGenerator) which
+/// carries 128 bits of state between draws.
+///
+/// `PureRandomGenerator` finishes its hasher into a `u64` for each draw, and
+/// writes that back to advance the state, so a stream of values drawn from one
+/// seed can only ever be in one of 2^64 states. That's plenty for most uses,
+/// but a long `sample_iter` stream or the shuffle of a very large slice can
+/// visit enough of them that the lost information starts to matter. This
+/// generator instead finishes the hasher with [`Hasher128::finish128`] and
+/// writes all 128 bits back.
+///
+/// Seeding works exactly as it does for `PureRandomGenerator`. Drawing is done
+/// through [`next_u128`](Self::next_u128), or the `RngCore` implementation,
+/// where `next_u64` and `next_u32` truncate a full 128-bit draw. The
+/// by-value convenience wrappers aren't provided, so use the `Rng` and
+/// `SliceRandom` traits from `rand` with a mutable reference instead.
+///
+/// The 128-bit aliases in [`hashers`](crate::hashers) are ready to use, eg.
+/// `SipPureRng128` behind the `siphash` feature.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "rand_08")]
+/// # {
+/// use pure_rng::{Hasher128, PureRandomGenerator128};
+/// use rand::{seq::SliceRandom, Rng};
+///
+/// fn deal<H: Hasher128 + Default + Clone>(rng: &PureRandomGenerator128<H>) -> Vec<u32> {
+///     let mut rng = rng.seed("deck");
+///     let mut cards: Vec<u32> = (0..1_000_000).collect();
+///     cards.shuffle(&mut rng);
+///
+///     let cut = rng.gen_range(0..cards.len());
+///     cards.rotate_left(cut);
+///     cards
+/// }
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    hasher: H,
+}
+
+impl<H> PureRandomGenerator128<H>
+where
+    H: Hasher128 + Default + Clone,
+{
+    /// Creates a new generator with the given hashable value as the seed.
+    ///
+    /// See [`PureRandomGenerator::new`](crate::PureRandomGenerator::new).
+    pub fn new(hashable: impl Hash) -> Self {
+        Self::default().seed(hashable)
+    }
+
+    /// Forks the generator, and advances the fork's state by hashing the given
+    /// value.
+    ///
+    /// See [`PureRandomGenerator::seed`](crate::PureRandomGenerator::seed).
+    pub fn seed(&self, hashable: impl Hash) -> Self {
+        let mut fork = self.clone();
+        hashable.hash(&mut fork.hasher);
+
+        fork
+    }
+
+    /// Forks the generator, and advances the fork's state by writing the
+    /// pre-computed hash of the given label.
+    ///
+    /// See [`PureRandomGenerator::seed_label`](crate::PureRandomGenerator::seed_label).
+    pub fn seed_label(&self, label: SeedLabel) -> Self {
+        let mut fork = self.clone();
+        fork.hasher.write_u64(label.hash());
+
+        fork
+    }
+
+    /// Draws the next 128-bit value.
+    pub fn next_u128(&mut self) -> u128 {
+        let val = self.hasher.finish128();
+
+        // Write the whole value back, so none of it is lost from the state.
+        self.hasher.write_u128(val);
+
+        val
+    }
+
+    /// Fills `dest` with bytes from successive 128-bit draws.
+    #[cfg_attr(not(any(feature = "rand_08", feature = "rand_09")), allow(dead_code))]
+    pub(crate) fn fill_bytes_via_u128(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(16) {
+            let bytes = self.next_u128().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::hash::Hasher;
+
+    use super::*;
+    use crate::{hashers::SplitMix64Hasher, StableHasher};
+
+    /// A 128-bit hasher for testing, made of two differently seeded 64-bit
+    /// ones.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct PairHasher(SplitMix64Hasher, SplitMix64Hasher);
+
+    impl Default for PairHasher {
+        fn default() -> Self {
+            Self(
+                SplitMix64Hasher::with_seed(1),
+                SplitMix64Hasher::with_seed(2),
+            )
+        }
+    }
+
+    impl Hasher for PairHasher {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes);
+            self.1.write(bytes);
+        }
+    }
+
+    impl Hasher128 for PairHasher {
+        fn finish128(&self) -> u128 {
+            self.0.finish() as u128 | (self.1.finish() as u128) << 64
+        }
+    }
+
+    type Rng128 = PureRandomGenerator128<StableHasher<PairHasher>>;
+
+    #[test]
+    fn test_next_u128() {
+        let mut rng = Rng128::new(1234);
+        let mut hasher = StableHasher::<PairHasher>::default();
+        1234.hash(&mut hasher);
+
+        for _ in 0..4 {
+            let expected = hasher.finish128();
+            hasher.write_u128(expected);
+
+            assert_eq!(rng.next_u128(), expected);
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let rng = Rng128::new(1234);
+
+        assert_eq!(rng.seed("a"), rng.seed("a"));
+        assert_ne!(rng.seed("a"), rng.seed("b"));
+        assert_eq!(rng.seed(1).seed(2), Rng128::new(1234).seed(1).seed(2));
+        assert_eq!(
+            rng.seed_label(crate::seed_label!("label")),
+            rng.seed(crate::seed_label!("label"))
+        );
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let rng = Rng128::new(1234);
+
+        let mut bytes = [0; 40];
+        rng.clone().fill_bytes_via_u128(&mut bytes);
+
+        let mut draws = rng;
+        let expected: Vec<u8> = (0..3)
+            .flat_map(|_| draws.next_u128().to_le_bytes())
+            .take(40)
+            .collect();
+        assert_eq!(bytes[..], expected[..]);
+    }
+}
@@ -0,0 +1,87 @@
+#![cfg(all(feature = "rapidhash", feature = "rand_08"))]
+
+use pure_rng::{PureGenerate, PureRng};
+#[cfg(not(feature = "derive"))]
+use pure_rng_derive::PureGenerate;
+use rand_distr::Normal;
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Monster {
+    #[pure(dist = Normal::new(20., 6.).unwrap())]
+    health: f32,
+    #[pure(range = 1..10)]
+    damage: i32,
+    hit_chance: f64,
+    #[pure(generate)]
+    loot: Loot,
+}
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Reordered {
+    #[pure(generate)]
+    loot: Loot,
+    hit_chance: f64,
+    #[pure(range = 1..10)]
+    damage: i32,
+    #[pure(dist = Normal::new(20., 6.).unwrap())]
+    health: f32,
+}
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Renamed {
+    #[pure(seed = "hit_chance")]
+    accuracy: f64,
+}
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Loot(u8, #[pure(range = 0..=3)] u8);
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Unit;
+
+#[derive(Debug, PartialEq, PureGenerate)]
+struct Generic<T, U> {
+    standard: T,
+    #[pure(generate)]
+    generate: U,
+    array: [T; 2],
+}
+
+#[test]
+fn test_fields_seeded_by_name() {
+    let rng = PureRng::new(1234);
+    let monster = Monster::generate(rng);
+
+    assert_eq!(
+        monster.health,
+        rng.seed("health").sample(Normal::new(20., 6.).unwrap())
+    );
+    assert_eq!(monster.damage, rng.seed("damage").gen_range(1..10));
+    assert_eq!(monster.hit_chance, rng.seed("hit_chance").gen::<f64>());
+    assert_eq!(monster.loot.0, rng.seed("loot").seed("0").gen::<u8>());
+    assert_eq!(monster.loot.1, rng.seed("loot").seed("1").gen_range(0..=3));
+}
+
+#[test]
+fn test_field_order_independent() {
+    let rng = PureRng::new(1234);
+    let monster = Monster::generate(rng);
+    let reordered = Reordered::generate(rng);
+
+    assert_eq!(monster.health, reordered.health);
+    assert_eq!(monster.damage, reordered.damage);
+    assert_eq!(monster.hit_chance, reordered.hit_chance);
+    assert_eq!(monster.loot, reordered.loot);
+    assert_eq!(Renamed::generate(rng).accuracy, monster.hit_chance);
+    assert_eq!(Unit::generate(rng), Unit);
+}
+
+#[test]
+fn test_generic() {
+    let rng = PureRng::new(1234);
+    let generic = Generic::<u8, Loot>::generate(rng);
+
+    assert_eq!(generic.standard, rng.seed("standard").gen::<u8>());
+    assert_eq!(generic.generate, Loot::generate(rng.seed("generate")));
+    assert_eq!(generic.array, rng.seed("array").gen::<[u8; 2]>());
+}
@@ -0,0 +1,125 @@
+#![cfg(all(feature = "rapidhash", feature = "loot", feature = "serde"))]
+
+use pure_rng::{
+    loot::{Loot, LootEntry, LootTable, Quantity},
+    PureRng,
+};
+
+fn expected() -> LootTable<String> {
+    let gems = LootTable::new(vec![
+        LootEntry::new("ruby", Loot::Item("ruby".into())),
+        LootEntry::new("opal", Loot::Item("opal".into())),
+    ]);
+
+    LootTable {
+        rolls: Quantity::new(1, 3),
+        unique: false,
+        entries: vec![
+            LootEntry::new("gold", Loot::Item("gold coin".into()))
+                .guaranteed()
+                .quantity(5, 20),
+            LootEntry::new("sword", Loot::Item("rusty sword".into())).weight(2.0),
+            LootEntry::new("gems", Loot::Table(Box::new(gems))).condition("boss"),
+            LootEntry::nothing("nothing").weight(5.0),
+        ],
+    }
+}
+
+#[test]
+fn test_ron() {
+    let table: LootTable<String> = ron::from_str(
+        r#"(
+            rolls: (min: 1, max: 3),
+            entries: [
+                (id: "gold", guaranteed: true, quantity: (min: 5, max: 20), item: Some("gold coin")),
+                (id: "sword", weight: 2.0, item: Some("rusty sword")),
+                (
+                    id: "gems",
+                    conditions: ["boss"],
+                    table: Some((entries: [(id: "ruby", item: Some("ruby")), (id: "opal", item: Some("opal"))])),
+                ),
+                (id: "nothing", weight: 5.0),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    assert_eq!(table, expected());
+}
+
+#[test]
+fn test_json() {
+    let table: LootTable<String> = serde_json::from_str(
+        r#"{
+            "rolls": { "min": 1, "max": 3 },
+            "entries": [
+                { "id": "gold", "guaranteed": true, "quantity": { "min": 5, "max": 20 }, "item": "gold coin" },
+                { "id": "sword", "weight": 2.0, "item": "rusty sword" },
+                {
+                    "id": "gems",
+                    "conditions": ["boss"],
+                    "table": { "entries": [{ "id": "ruby", "item": "ruby" }, { "id": "opal", "item": "opal" }] }
+                },
+                { "id": "nothing", "weight": 5.0 }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(table, expected());
+    assert_eq!(
+        serde_json::from_str::<LootTable<String>>(&serde_json::to_string(&table).unwrap()).unwrap(),
+        table
+    );
+}
+
+#[test]
+fn test_item_and_table() {
+    let entry = serde_json::from_str::<LootEntry<String>>(
+        r#"{ "id": "both", "item": "sword", "table": { "entries": [] } }"#,
+    );
+
+    assert!(entry.is_err());
+}
+
+#[test]
+fn test_toml() {
+    let table: LootTable<String> = toml::from_str(
+        r#"
+            rolls = { min = 1, max = 3 }
+
+            [[entries]]
+            id = "gold"
+            guaranteed = true
+            quantity = { min = 5, max = 20 }
+            item = "gold coin"
+
+            [[entries]]
+            id = "sword"
+            weight = 2.0
+            item = "rusty sword"
+
+            [[entries]]
+            id = "gems"
+            conditions = ["boss"]
+            table = { entries = [{ id = "ruby", item = "ruby" }, { id = "opal", item = "opal" }] }
+
+            [[entries]]
+            id = "nothing"
+            weight = 5.0
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(table, expected());
+}
+
+#[test]
+fn test_fixed_quantity() {
+    let entry: LootEntry<String> =
+        serde_json::from_str(r#"{ "id": "arrows", "quantity": 12, "item": "arrow" }"#).unwrap();
+    assert_eq!(entry.quantity, Quantity::new(12, 12));
+
+    let table = LootTable::new(vec![entry]);
+    assert_eq!(table.roll(PureRng::new("quiver"))[0].quantity, 12);
+}
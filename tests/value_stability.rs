@@ -0,0 +1,513 @@
+//! Golden test vectors, guarding the value-stability policy described on
+//! [`pure_rng::STREAM_VERSION`].
+//!
+//! If any of these fail then values which users may have persisted have
+//! changed. Don't update the vectors without bumping `STREAM_VERSION`.
+
+use pure_rng::STREAM_VERSION;
+
+#[test]
+fn test_stream_version() {
+    assert_eq!(STREAM_VERSION, 1);
+}
+
+#[cfg(all(feature = "rapidhash", any(feature = "rand_08", feature = "rand_09")))]
+mod rapidhash {
+    use pure_rng::{PureRng, PureStream};
+    #[cfg(feature = "rand_08")]
+    use rand::RngCore;
+    #[cfg(not(feature = "rand_08"))]
+    use rand09::RngCore;
+
+    fn rng() -> PureRng {
+        PureRng::new(1234)
+    }
+
+    #[test]
+    fn test_seed() {
+        let values: Vec<u64> = (0..4u64)
+            .map(|i| rng().seed(("seed", i)).next_u64())
+            .collect();
+
+        assert_eq!(
+            values,
+            [
+                0xd4ff4aba7a40004b,
+                0x3a9c3abcf43ba00f,
+                0x052d898fa2bdd23d,
+                0xf73c84572d68f2d8,
+            ]
+        );
+
+        assert_eq!(PureRng::new("root").next_u64(), 0x8c0b7e58dbe7a931);
+    }
+
+    #[test]
+    fn test_seed_label() {
+        let label = pure_rng::seed_label!("seed_label");
+        let value = rng().seed_label(label).next_u64();
+
+        assert_eq!(value, 0x07772d27fe7089bd);
+        assert_eq!(value, rng().seed(label).next_u64());
+    }
+
+    #[test]
+    fn test_into_stream() {
+        let mut stream = rng().seed("into_stream").into_stream::<PureStream>();
+        let values: Vec<u64> = (0..4).map(|_| stream.next_u64()).collect();
+
+        assert_eq!(
+            values,
+            [
+                0x8271ec4eb25ae096,
+                0xee09137e705221b4,
+                0x499553b20700907a,
+                0xfddc04024fcb1db3,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_state() {
+        let (state, rng) = rng().seed("to_state").to_state();
+        assert_eq!(state.to_string(), "0101000000755bc77de6e5920f3c391743");
+
+        let mut restored = PureRng::from_state(state).unwrap();
+        assert!(restored == rng);
+        assert_eq!(restored.next_u64(), 0xd292011207bd67d2);
+    }
+
+    #[test]
+    fn test_next_u64() {
+        let mut rng = rng().seed("next_u64");
+        let values: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+
+        assert_eq!(
+            values,
+            [
+                0x7207dc0e623a8989,
+                0xd299a2c376fef0e1,
+                0x56d2b811b6f004ed,
+                0x2f70509ef0d72c8d,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let mut bytes = [0u8; 12];
+        rng().seed("fill_bytes").fill_bytes(&mut bytes);
+
+        assert_eq!(
+            bytes,
+            [113, 62, 43, 195, 42, 216, 237, 237, 53, 98, 115, 93]
+        );
+    }
+
+    #[cfg(feature = "rand_08")]
+    mod rand_08 {
+        #[cfg(feature = "alloc")]
+        use pure_rng::rand_08::seq::index;
+        use pure_rng::rand_08::seq::SlicePureRandom;
+
+        use super::rng;
+
+        #[test]
+        fn test_gen_range() {
+            let ints: Vec<i32> = (0..8)
+                .map(|i| rng().seed(("gen_range", i)).gen_range(-100..100))
+                .collect();
+            assert_eq!(ints, [-85, 0, 16, 25, 85, -35, 80, 17]);
+
+            let floats: Vec<f64> = (0..3)
+                .map(|i| rng().seed(("gen_range_f64", i)).gen_range(0.0..1.0))
+                .collect();
+            assert_eq!(
+                floats,
+                [0.5786630083301174, 0.20266498835137248, 0.5380160837728736]
+            );
+        }
+
+        #[test]
+        fn test_shuffle() {
+            let mut deck: Vec<u32> = (0..10).collect();
+            deck.shuffle(rng().seed("shuffle"));
+
+            assert_eq!(deck, [0, 6, 7, 5, 3, 8, 2, 9, 1, 4]);
+        }
+
+        #[test]
+        #[cfg(feature = "alloc")]
+        fn test_index_sample() {
+            let indices = index::sample(rng().seed("sample"), 100, 5).into_vec();
+
+            assert_eq!(indices, [27, 15, 76, 1, 53]);
+        }
+    }
+
+    #[cfg(feature = "rand_09")]
+    mod rand_09 {
+        #[cfg(feature = "alloc")]
+        use pure_rng::rand_09::seq::index;
+        use pure_rng::rand_09::seq::SlicePureRandom;
+
+        use super::rng;
+
+        #[test]
+        fn test_random_range() {
+            let ints: Vec<i32> = (0..8)
+                .map(|i| rng().seed(("gen_range", i)).random_range(-100..100))
+                .collect();
+            assert_eq!(ints, [-85, 0, 80, 25, 85, -35, 80, 17]);
+
+            let floats: Vec<f64> = (0..3)
+                .map(|i| rng().seed(("gen_range_f64", i)).random_range(0.0..1.0))
+                .collect();
+            assert_eq!(
+                floats,
+                [0.5786630083301174, 0.20266498835137248, 0.5380160837728736]
+            );
+        }
+
+        #[test]
+        fn test_shuffle() {
+            let mut deck: Vec<u32> = (0..10).collect();
+            deck.shuffle(rng().seed("shuffle"));
+
+            assert_eq!(deck, [1, 6, 2, 0, 9, 5, 8, 4, 7, 3]);
+        }
+
+        #[test]
+        #[cfg(feature = "alloc")]
+        fn test_index_sample() {
+            let indices = index::sample(rng().seed("sample"), 100, 5).into_vec();
+
+            assert_eq!(indices, [27, 93, 15, 36, 78]);
+        }
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "noise"))]
+mod noise {
+    use pure_rng::{
+        noise::{Fbm, Noise, OpenSimplex2, Perlin, ValueNoise, Worley, WorleyOutput},
+        PureRng,
+    };
+
+    fn rng() -> PureRng {
+        PureRng::new(1234).seed("noise")
+    }
+
+    #[test]
+    fn test_value_noise() {
+        let noise = ValueNoise::new(rng());
+
+        assert_eq!(noise.get([0.3]), 0.7399682608435735);
+        assert_eq!(noise.get([0.3, 1.25]), -0.3811109517036592);
+        assert_eq!(noise.get([0.3, 1.25, -2.75]), -0.32747921460879126);
+    }
+
+    #[test]
+    fn test_perlin() {
+        let noise = Perlin::new(rng());
+
+        assert_eq!(noise.get([0.3]), 0.27424824771195616);
+        assert_eq!(noise.get([0.3, 1.25]), -0.14365849865276856);
+        assert_eq!(noise.get([0.3, 1.25, -2.75]), -0.34725028572484);
+        assert_eq!(noise.get([0.3, 1.25, -2.75, 3.125]), -0.04630838127254457);
+    }
+
+    #[test]
+    fn test_open_simplex_2() {
+        let noise = OpenSimplex2::new(rng());
+
+        assert_eq!(noise.get([0.3]), 0.43105248122926415);
+        assert_eq!(noise.get([0.3, 1.25]), 0.6221086459337772);
+        assert_eq!(noise.get([0.3, 1.25, -2.75]), 0.21267989170126103);
+        assert_eq!(noise.get([0.3, 1.25, -2.75, 3.125]), -0.3737120062154596);
+    }
+
+    #[test]
+    fn test_fbm() {
+        let noise = Fbm::new(OpenSimplex2::new(rng()));
+
+        assert_eq!(noise.get([0.3, 1.25]), 0.2113879827414597);
+    }
+
+    #[test]
+    fn test_worley() {
+        let mut noise = Worley::new(rng());
+
+        assert_eq!(
+            noise.distances([0.3, 1.25]),
+            (0.5217625332409755, 1.0609601544769447)
+        );
+        assert_eq!(noise.nearest_site([0.3, 1.25, -2.75]).cell, [0, 1, -3]);
+
+        noise.output = WorleyOutput::F2MinusF1;
+        assert_eq!(noise.get([0.3, 1.25, -2.75, 3.125]), -0.9571906665790331);
+    }
+}
+
+#[cfg(any(feature = "rand_08", feature = "rand_09"))]
+mod hashers {
+    #[allow(unused_imports)]
+    use pure_rng::hashers::*;
+    #[cfg(feature = "rand_08")]
+    use rand::RngCore;
+    #[cfg(not(feature = "rand_08"))]
+    use rand09::RngCore;
+
+    /// Defines a test checking values from `seed()` and `next_u64()` for the
+    /// given generator.
+    macro_rules! golden {
+        ($(#[$attr:meta])* $name:ident: $rng:ty, $seeds:expr, $stream:expr) => {
+            $(#[$attr])*
+            #[test]
+            fn $name() {
+                let rng = <$rng>::new(1234);
+
+                let seeds: Vec<u64> = (0..3u64)
+                    .map(|i| rng.seed(("seed", i)).next_u64())
+                    .collect();
+                assert_eq!(seeds, $seeds);
+
+                let mut stream = rng.seed("next_u64");
+                let values: Vec<u64> = (0..3).map(|_| stream.next_u64()).collect();
+                assert_eq!(values, $stream);
+            }
+        };
+    }
+
+    golden!(
+        #[cfg(feature = "siphash")]
+        test_siphash: SipPureRng,
+        [0x88100f0db0713ebf, 0x447e689ace4d0147, 0x9ad2b9fcc116adca],
+        [0x6d5130a2dc3bae16, 0x25c9380702be71ca, 0xf9f67d476725b743]
+    );
+
+    golden!(
+        #[cfg(feature = "wyhash")]
+        test_wyhash: WyPureRng,
+        [0x912626406a8d6c46, 0xab12e29eb69cc32d, 0x9bd37501e77606b3],
+        [0xd50162d41a82b505, 0xe564cc52f757a583, 0x0addc54d9a7dc4be]
+    );
+
+    golden!(
+        #[cfg(feature = "xxh3")]
+        test_xxh3: Xxh3PureRng,
+        [0xd1e4cab0d1085e3f, 0x19b8a5aa8d873509, 0x46441a4b66e9e11b],
+        [0x890048177e187f41, 0x5339d2dbb577646d, 0xf507466c23e11bfe]
+    );
+
+    golden!(
+        #[cfg(all(feature = "fxhash", target_pointer_width = "64"))]
+        test_fxhash: FxPureRng,
+        [0x2ec36e44028a583a, 0x48432f733a7f004f, 0x7845548c4a63e223],
+        [0x98e6080702b6fc40, 0x98f944c216e903ea, 0x602d8ff46aef830f]
+    );
+
+    golden!(
+        #[cfg(feature = "foldhash")]
+        test_foldhash: FoldPureRng,
+        [0x0891aac373c1bf03, 0xe1ae3fdc3382f46f, 0x2f610e3bb1671189],
+        [0xf0d38b2d67db90c3, 0x4deb943ba6609b47, 0x1bdfe611d21c680c]
+    );
+
+    /// Defines a test checking values from `seed()` and `next_u128()`, then
+    /// `next_u64()`, for the given 128-bit generator.
+    macro_rules! golden128 {
+        ($(#[$attr:meta])* $name:ident: $rng:ty, $seeds:expr, $stream:expr) => {
+            $(#[$attr])*
+            #[test]
+            fn $name() {
+                let rng = <$rng>::new(1234);
+
+                let seeds: Vec<u128> = (0..3u64)
+                    .map(|i| rng.seed(("seed", i)).next_u128())
+                    .collect();
+                assert_eq!(seeds, $seeds);
+
+                let mut stream = rng.seed("next_u64");
+                let values: Vec<u64> = (0..3).map(|_| stream.next_u64()).collect();
+                assert_eq!(values, $stream);
+            }
+        };
+    }
+
+    golden128!(
+        #[cfg(feature = "siphash")]
+        test_siphash_128: SipPureRng128,
+        [
+            0xc22fb5e625ab08ac0ea2c8e164c9476d,
+            0x6aeb8fd7b0020aa2987a0aeeb9272205,
+            0xacc1974a006190882e3dcc78f60a2a52,
+        ],
+        [0xa3dbc3281b5105c4, 0x3200172213294720, 0x9dd4c6c63c18a291]
+    );
+
+    golden128!(
+        #[cfg(feature = "xxh3")]
+        test_xxh3_128: Xxh3PureRng128,
+        [
+            0x584bebf3400d72930a111acc0a45ddd0,
+            0xeb7ead9e5037c4b3bc847bf6db3ac538,
+            0xd4573d4e1e46e10481e932dd8b1ccbb7,
+        ],
+        [0x98be8fed31c7f26c, 0xaaa3cf2c8fba657b, 0x42599ccb7dbb2e66]
+    );
+
+    golden!(
+        test_splitmix: SplitMixPureRng,
+        [0xcfa4d3e1a812ee9e, 0xb77fd444870847f2, 0x5691dac9da1e7bf3],
+        [0x71fe31fe080e46a7, 0x3e592f6479714f33, 0x17bc8d3540aa60a9]
+    );
+}
+
+#[cfg(feature = "seed_code")]
+mod seed_code {
+    use pure_rng::seed_code::SeedCode;
+
+    #[test]
+    fn test_encodings() {
+        let code = SeedCode::new(0x0123456789abcdef);
+
+        assert_eq!(code.to_string(), "04HM-ASW9-NF6Y-ZNWB");
+        assert_eq!(
+            code.words().to_string(),
+            "axle gable idol lawn noble raven talon whisk tree"
+        );
+        assert_eq!(SeedCode::new(0).to_string(), "0000-0000-0000-109Z");
+    }
+
+    #[test]
+    fn test_hashed_input() {
+        assert_eq!(SeedCode::from_input("glacier").value(), 5850425863482202603);
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "dice"))]
+mod dice {
+    use pure_rng::{dice::Expr, PureRng};
+
+    fn values(expr: &str) -> Vec<i64> {
+        let expr: Expr = expr.parse().unwrap();
+        let roll = expr.roll(PureRng::new(1234));
+
+        roll.dice.iter().map(|die| die.value).collect()
+    }
+
+    #[test]
+    fn test_rolls() {
+        assert_eq!(values("4d6"), [5, 3, 4, 1]);
+        assert_eq!(values("d%"), [83]);
+        assert_eq!(values("4dF"), [1, 0, 0, -1]);
+        assert_eq!(values("6d4!"), [6, 2, 3, 1, 2, 1]);
+
+        let expr: Expr = "4d6kh3+2".parse().unwrap();
+        assert_eq!(expr.roll(PureRng::new(1234)).total, 14);
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "loot"))]
+mod loot {
+    use pure_rng::{
+        loot::{Loot, LootEntry, LootTable, Quantity},
+        PureRng,
+    };
+
+    #[test]
+    fn test_roll() {
+        let table = LootTable {
+            rolls: Quantity::new(2, 4),
+            unique: false,
+            entries: vec![
+                LootEntry::new("gold", Loot::Item("gold"))
+                    .guaranteed()
+                    .quantity(5, 20),
+                LootEntry::new("sword", Loot::Item("sword")).weight(2.0),
+                LootEntry::new("shield", Loot::Item("shield")),
+                LootEntry::nothing("nothing").weight(3.0),
+            ],
+        };
+
+        let drops: Vec<(&str, u32)> = table
+            .roll(PureRng::new(1234))
+            .iter()
+            .map(|drop| (*drop.item, drop.quantity))
+            .collect();
+
+        assert_eq!(drops, [("gold", 14), ("sword", 1), ("sword", 1)]);
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "alloc"))]
+mod bag {
+    use pure_rng::{bag::ShuffleBag, PureRng};
+
+    #[test]
+    fn test_draws() {
+        let mut bag = ShuffleBag::new(vec!['I', 'J', 'L', 'O', 'S', 'T', 'Z'], PureRng::new(1234));
+        let drawn: String = (0..14).map(|_| *bag.draw()).collect();
+
+        assert_eq!(drawn, "ZTLISOJOLSTIJZ");
+        assert_eq!(*bag.draw_at(1_000_000), 'J');
+    }
+}
+
+#[cfg(feature = "rapidhash")]
+mod permutation {
+    use pure_rng::PureRng;
+
+    #[test]
+    fn test_permutation() {
+        let small = PureRng::new(1234).permutation(10);
+        assert_eq!(
+            small.iter().collect::<Vec<_>>(),
+            [8, 2, 0, 5, 3, 7, 6, 4, 9, 1]
+        );
+
+        let large = PureRng::new(1234).permutation(1_000_000_000_000);
+        assert_eq!(large.get(0), 906214083825);
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "spatial"))]
+mod spatial {
+    use pure_rng::{
+        spatial::{JitteredGrid, PointSampler, PoissonDisk},
+        PureRng,
+    };
+
+    #[test]
+    fn test_points() {
+        let grid = JitteredGrid::new(PureRng::new(1234), 1.);
+        assert_eq!(grid.point([0, 0]), [0.6003960944710787, 0.8722037360662815]);
+
+        let mut points = PoissonDisk::new(PureRng::new(1234), 1.).points([0., 0.], [2., 2.]);
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            points,
+            [
+                [0.5338456764679542, 0.26045022768750936],
+                [1.2481992215563689, 1.8340757898146411],
+                [1.3790907935804095, 0.8385688375612599],
+            ]
+        );
+    }
+}
+
+#[cfg(all(feature = "rapidhash", feature = "alloc"))]
+mod weighted {
+    use pure_rng::{PureRng, PureWeightedTable};
+
+    #[test]
+    fn test_samples() {
+        let table = PureWeightedTable::new(vec!['a', 'b', 'c', 'd'], vec![1., 0., 2., 7.]).unwrap();
+        let samples: String = (0..16u32)
+            .map(|i| *table.sample(PureRng::new(1234).seed(i)))
+            .collect();
+
+        assert_eq!(samples, "adcdcdccddccdddc");
+    }
+}